@@ -0,0 +1,116 @@
+/// Above this score or increment, all scores are scaled down to avoid overflowing.
+/// A power of two, so that rescaling keeps the ratios exact.
+const RESCALE_LIMIT: f64 = (1u128 << 100) as f64;
+
+/// Activity scores of clauses for CDCL-style clause database reduction, indexed like the clauses:
+/// a clause is bumped each time it takes part in a conflict, and all scores decay over time,
+/// so that the clauses involved in recent conflicts score the highest.
+///
+/// As in MiniSat, the decay is applied lazily by growing the bump increment instead of shrinking every score,
+/// and the scores are all rescaled when they grow too large. Only the relative scores are meaningful.
+#[derive(Debug, Clone)]
+pub struct Activity {
+    scores: Vec<f64>,
+    increment: f64,
+}
+
+impl Activity {
+    /// Creates the scores of `n` clauses, all at 0.
+    pub fn new(n: usize) -> Self {
+        Self {
+            scores: vec![0.0; n],
+            increment: 1.0,
+        }
+    }
+
+    /// Appends the score of a new clause, at 0, and returns its index.
+    pub fn push(&mut self) -> usize {
+        self.scores.push(0.0);
+        self.scores.len() - 1
+    }
+
+    /// Bumps the score of the clause at `index`, for its involvement in a conflict.
+    /// Panics if `index` is out of bounds.
+    pub fn bump(&mut self, index: usize) {
+        self.scores[index] += self.increment;
+        if self.scores[index] > RESCALE_LIMIT {
+            self.rescale();
+        }
+    }
+
+    /// Decays all scores by `factor`, typically 0.999: the past bumps then weigh `factor` times less than the next ones.
+    /// Panics if `factor` is not in `(0, 1]`.
+    pub fn decay(&mut self, factor: f64) {
+        assert!(
+            factor > 0.0 && factor <= 1.0,
+            "decay factor {factor} not in (0, 1]"
+        );
+        self.increment /= factor;
+        if self.increment > RESCALE_LIMIT {
+            self.rescale();
+        }
+    }
+
+    /// Scales all scores and the increment down by `RESCALE_LIMIT`.
+    fn rescale(&mut self) {
+        for score in &mut self.scores {
+            *score /= RESCALE_LIMIT;
+        }
+        self.increment /= RESCALE_LIMIT;
+    }
+
+    /// Returns the score of the clause at `index`, or `None` if `index` is out of bounds.
+    pub fn score(&self, index: usize) -> Option<f64> {
+        self.scores.get(index).copied()
+    }
+
+    /// Returns the scores, in clause order.
+    pub fn scores(&self) -> &[f64] {
+        &self.scores
+    }
+
+    /// Returns the clause indices by decreasing score, the order in which to keep clauses when reducing the database.
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<_> = (0..self.scores.len()).collect();
+        ranking.sort_by(|&a, &b| self.scores[b].total_cmp(&self.scores[a]));
+
+        ranking
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bump_and_decay() {
+        let mut activity = Activity::new(3);
+        activity.bump(0);
+        activity.decay(0.5);
+        activity.bump(1);
+        activity.bump(2);
+        activity.bump(2);
+
+        assert_eq!(activity.scores(), &[1.0, 2.0, 4.0]);
+        assert_eq!(activity.ranking(), vec![2, 1, 0]);
+        assert_eq!(activity.push(), 3);
+        assert_eq!(activity.score(3), Some(0.0));
+        assert_eq!(activity.score(4), None);
+    }
+
+    #[test]
+    fn rescale() {
+        let mut activity = Activity::new(2);
+        activity.bump(0);
+        for _ in 0..1000 {
+            activity.decay(0.5);
+        }
+        activity.bump(1);
+
+        // 2^1000 exceeds the limit, the scores are rescaled keeping their ratio
+        let scores = activity.scores();
+        assert!(scores[1] <= RESCALE_LIMIT);
+        assert_eq!(scores[1] / scores[0], 2f64.powi(1000));
+        assert_eq!(activity.ranking(), vec![1, 0]);
+    }
+}
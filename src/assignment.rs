@@ -0,0 +1,129 @@
+use bool_vec::{boolvec, BoolVec};
+
+use crate::literal::Literal;
+
+/// A partial assignment of the variables: each variable is true, false, or unassigned.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Assignment(Vec<Option<bool>>);
+
+impl Assignment {
+    /// Creates an assignment of `n` unassigned variables.
+    pub fn new(n: usize) -> Self {
+        Self(vec![None; n])
+    }
+
+    /// Creates a complete assignment from the given variable values.
+    pub fn from_vars(vars: &BoolVec) -> Self {
+        vars.into_iter().map(Some).collect()
+    }
+
+    /// Returns the number of variables, assigned or not.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether there are no variables.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the value of the variable at `index`, `None` if it is unassigned or not present.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.0.get(index).copied().flatten()
+    }
+
+    /// Sets the value of the variable at `index`.
+    /// Returns `None` if the variable is not present.
+    pub fn set(&mut self, index: usize, value: bool) -> Option<()> {
+        *self.0.get_mut(index)? = Some(value);
+        Some(())
+    }
+
+    /// Unassigns the variable at `index`.
+    /// Returns `None` if the variable is not present.
+    pub fn unset(&mut self, index: usize) -> Option<()> {
+        *self.0.get_mut(index)? = None;
+        Some(())
+    }
+
+    /// Sets the variable of `lit` so that `lit` is true.
+    /// Returns `None` if the variable is not present.
+    pub fn assign_literal(&mut self, lit: Literal) -> Option<()> {
+        self.set(lit.index(), !lit.is_negated())
+    }
+
+    /// Returns the value of the literal (that is, possibly negated), `None` if its variable is unassigned or not present.
+    pub fn eval(&self, lit: Literal) -> Option<bool> {
+        self.get(lit.index()).map(|v| v ^ lit.is_negated())
+    }
+
+    /// Returns whether every variable is assigned.
+    pub fn is_complete(&self) -> bool {
+        self.0.iter().all(Option::is_some)
+    }
+
+    /// Returns the number of assigned variables.
+    pub fn num_assigned(&self) -> usize {
+        self.0.iter().filter(|v| v.is_some()).count()
+    }
+
+    /// Returns the variable values if the assignment is complete, `None` otherwise.
+    pub fn to_vars(&self) -> Option<BoolVec> {
+        let mut vars = boolvec![false; self.len()];
+        for (i, value) in self.0.iter().enumerate() {
+            vars.set(i, (*value)?);
+        }
+
+        Some(vars)
+    }
+}
+
+impl FromIterator<Option<bool>> for Assignment {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = Option<bool>>,
+    {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial() {
+        let mut assignment = Assignment::new(3);
+        assert_eq!(assignment.num_assigned(), 0);
+
+        assignment.set(0, true).unwrap();
+        assignment.assign_literal(Literal::from_cnf(-2)).unwrap();
+        assert_eq!(assignment.set(3, true), None);
+
+        assert_eq!(assignment.get(0), Some(true));
+        assert_eq!(assignment.get(1), Some(false));
+        assert_eq!(assignment.get(2), None);
+        assert_eq!(assignment.eval(Literal::from_cnf(-1)), Some(false));
+        assert_eq!(assignment.eval(Literal::from_cnf(-2)), Some(true));
+        assert_eq!(assignment.eval(Literal::from_cnf(3)), None);
+        assert!(!assignment.is_complete());
+        assert_eq!(assignment.to_vars(), None);
+
+        assignment.set(2, true).unwrap();
+        assert!(assignment.is_complete());
+        assert_eq!(
+            assignment.to_vars(),
+            Some(BoolVec::from([true, false, true]))
+        );
+
+        assignment.unset(0).unwrap();
+        assert_eq!(assignment.num_assigned(), 2);
+    }
+
+    #[test]
+    fn from_vars() {
+        let vars = BoolVec::from([false, true]);
+
+        assert_eq!(Assignment::from_vars(&vars).to_vars(), Some(vars));
+    }
+}
@@ -0,0 +1,67 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{instance::Instance, solver::SolveResult};
+
+/// Parses and solves every `.cnf` file of the directory `dir` (not recursively), in path order.
+/// Returns, for each file, its path, the result of `solver` and the time spent in `solver`.
+/// A file that fails to parse does not abort the run: its error is recorded in place of the result,
+/// with a zero duration.
+/// Returns an error if the directory cannot be read.
+pub fn run_dir<P: AsRef<Path>>(
+    dir: P,
+    solver: impl Fn(&Instance) -> SolveResult,
+) -> io::Result<Vec<(PathBuf, io::Result<SolveResult>, Duration)>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "cnf"));
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| match Instance::from_file(&path) {
+            Ok(instance) => {
+                let start = Instant::now();
+                let result = solver(&instance);
+                (path, Ok(result), start.elapsed())
+            }
+            Err(error) => (path, Err(error), Duration::ZERO),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_dir() {
+        let dir = std::env::temp_dir().join(format!("sat_lab_batch_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a_sat.cnf"), "p cnf 2 1\n1 -2 0\n").unwrap();
+        fs::write(dir.join("b_unsat.cnf"), "p cnf 1 2\n1 0\n-1 0\n").unwrap();
+        fs::write(dir.join("c_malformed.cnf"), "p cnf 1 1\nx 0\n").unwrap();
+        fs::write(dir.join("d_ignored.txt"), "p cnf 1 1\n1 0\n").unwrap();
+
+        let results = super::run_dir(&dir, Instance::solve);
+        fs::remove_dir_all(&dir).unwrap();
+        let results = results.unwrap();
+
+        let names: Vec<_> = results
+            .iter()
+            .map(|(path, _, _)| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["a_sat.cnf", "b_unsat.cnf", "c_malformed.cnf"]);
+
+        assert!(results[0].1.as_ref().unwrap().is_sat());
+        assert!(results[1].1.as_ref().unwrap().is_unsat());
+        assert!(results[2].1.is_err());
+        assert_eq!(results[2].2, Duration::ZERO);
+
+        assert!(super::run_dir(dir.join("missing"), Instance::solve).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use std::io::{self, Read, Write};
+
+use crate::{
+    clause::Clause, instance::Instance, literal::Literal, parse::referred_vars,
+    quantifier::Quantifier,
+};
+
+/// Magic bytes opening the binary encoding.
+const MAGIC: &[u8; 4] = b"SATB";
+
+/// Version of the binary encoding, incremented on incompatible changes.
+const VERSION: u8 = 1;
+
+/// Upper bound of the capacities reserved from untrusted lengths.
+const MAX_RESERVED: usize = 1 << 16;
+
+/// Upper bound of the number of variables read, as their values are allocated at once.
+const MAX_VARS: usize = u32::MAX as usize;
+
+impl Instance {
+    /// Writes the instance in a compact binary encoding, read back by `read_binary`.
+    /// Meant for caching, it is much faster to load than DIMACS but is not an interchange format.
+    ///
+    /// The encoding is the magic `SATB` and a version byte, followed by the number of variables,
+    /// the quantifier prefix and the clauses, all length-prefixed.
+    /// Integers are LEB128 varints, and literals are zigzag-encoded CNF integers.
+    /// The current variable values are not written.
+    pub fn write_binary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        write_varint(w, self.num_vars() as u64)?;
+
+        write_varint(w, self.quantifiers().len() as u64)?;
+        for (quantifier, vars) in self.quantifiers() {
+            w.write_all(&[quantifier.as_char() as u8])?;
+            write_varint(w, vars.len() as u64)?;
+            for &var in vars {
+                write_varint(w, var as u64)?;
+            }
+        }
+
+        write_varint(w, self.get_clauses().len() as u64)?;
+        for clause in self.clauses() {
+            write_varint(w, clause.len() as u64)?;
+            for lit in clause.get_literals() {
+                let cnf = lit.as_cnf() as i64;
+                write_varint(w, ((cnf << 1) ^ (cnf >> 63)) as u64)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads an instance written by `write_binary`, with all variables initialized at 0.
+    /// Returns an error if the magic or the version do not match, or if the content is truncated or malformed.
+    /// An `InvalidData` error is returned if the content refers to variables beyond the number of variables,
+    /// or if this number exceeds `u32::MAX` or `Literal::MAX_INDEX + 1`.
+    pub fn read_binary<R: Read>(r: &mut R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC || read_byte(r)? != VERSION {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+        let n = read_len(r)?;
+        if n > MAX_VARS.min(Literal::MAX_INDEX + 1) {
+            return Err(io::ErrorKind::InvalidData.into());
+        }
+
+        let num_quantifiers = read_len(r)?;
+        let mut quantifiers = Vec::with_capacity(num_quantifiers.min(MAX_RESERVED));
+        for _ in 0..num_quantifiers {
+            let quantifier =
+                Quantifier::from_char(read_byte(r)? as char).ok_or(io::ErrorKind::InvalidInput)?;
+            let len = read_len(r)?;
+            let vars = (0..len)
+                .map(|_| match read_len(r)? {
+                    var if var < n => Ok(var),
+                    _ => Err(io::ErrorKind::InvalidData.into()),
+                })
+                .collect::<io::Result<_>>()?;
+            quantifiers.push((quantifier, vars));
+        }
+
+        let m = read_len(r)?;
+        let mut clauses = Vec::with_capacity(m.min(MAX_RESERVED));
+        for _ in 0..m {
+            let len = read_len(r)?;
+            let clause = (0..len)
+                .map(|_| read_literal(r))
+                .collect::<io::Result<Vec<_>>>()?;
+            clauses.push(Clause::new(clause));
+        }
+        if referred_vars(&clauses) > n {
+            return Err(io::ErrorKind::InvalidData.into());
+        }
+
+        let mut instance = Self::with_clauses(n, clauses);
+        instance.set_quantifiers(quantifiers);
+        Ok(instance)
+    }
+}
+
+fn write_varint<W: Write>(w: &mut W, mut value: u64) -> io::Result<()> {
+    let mut buf = [0; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    w.write_all(&buf[..len])
+}
+
+fn read_byte<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut byte = [0];
+    r.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_varint<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = read_byte(r)?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(io::ErrorKind::InvalidInput.into())
+}
+
+fn read_len<R: Read>(r: &mut R) -> io::Result<usize> {
+    usize::try_from(read_varint(r)?).map_err(|_| io::ErrorKind::InvalidInput.into())
+}
+
+fn read_literal<R: Read>(r: &mut R) -> io::Result<Literal> {
+    let zigzag = read_varint(r)?;
+    let cnf = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+    match isize::try_from(cnf) {
+        Ok(cnf) if cnf != 0 && cnf.unsigned_abs() - 1 <= Literal::MAX_INDEX => {
+            Ok(Literal::from_cnf(cnf))
+        }
+        _ => Err(io::ErrorKind::InvalidInput.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let mut instance = Instance::with_clauses(
+            300,
+            vec![
+                Clause::from_cnf(vec![1, -2, 300]),
+                Clause::from_cnf(vec![]),
+                Clause::from_cnf(vec![-300, 64, -65]),
+            ],
+        );
+        instance.set_quantifiers(vec![
+            (Quantifier::Forall, vec![0, 1]),
+            (Quantifier::Exists, vec![299]),
+        ]);
+
+        let mut bytes = vec![];
+        instance.write_binary(&mut bytes).unwrap();
+        assert_eq!(&bytes[..5], b"SATB\x01");
+
+        let read = Instance::read_binary(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read.num_vars(), 300);
+        assert_eq!(read.get_clauses(), instance.get_clauses());
+        assert_eq!(read.quantifiers(), instance.quantifiers());
+    }
+
+    #[test]
+    fn malformed() {
+        let mut bytes = vec![];
+        Instance::with_clauses(2, vec![Clause::from_cnf(vec![1, -2])])
+            .write_binary(&mut bytes)
+            .unwrap();
+
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(Instance::read_binary(&mut &truncated[..]).is_err());
+
+        let mut version = bytes.clone();
+        version[4] = VERSION + 1;
+        assert!(Instance::read_binary(&mut version.as_slice()).is_err());
+
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        assert!(Instance::read_binary(&mut magic.as_slice()).is_err());
+
+        let zero_literal = b"SATB\x01\x02\x00\x01\x01\x00";
+        assert!(Instance::read_binary(&mut &zero_literal[..]).is_err());
+
+        let literal_out_of_range = b"SATB\x01\x01\x00\x01\x01\x0a";
+        let err = Instance::read_binary(&mut &literal_out_of_range[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let quantifier_out_of_range = b"SATB\x01\x01\x01e\x01\x01\x00";
+        let err = Instance::read_binary(&mut &quantifier_out_of_range[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let huge_n = b"SATB\x01\xff\xff\xff\xff\xff\xff\xff\xff\x7f\x00\x00";
+        let err = Instance::read_binary(&mut &huge_n[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,11 +1,35 @@
-use std::fmt;
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+};
 
 use bool_vec::BoolVec;
 
-use crate::literal::*;
+use crate::{assignment::Assignment, literal::*};
+
+/// The value of a clause under a partial assignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClauseValue {
+    /// A literal is true.
+    True,
+    /// All literals are false.
+    False,
+    /// No literal is true, and some are unassigned.
+    Unknown,
+}
+
+/// The origin of a clause of an instance, see `Instance::clause_kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ClauseKind {
+    /// A clause of the formula.
+    #[default]
+    Original,
+    /// A clause learned by a solver, implied by the original clauses and removable.
+    Learned,
+}
 
 /// A Clause is a set of Literals
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Clause(Vec<Literal>);
 
 impl Clause {
@@ -24,7 +48,7 @@ impl Clause {
         I: IntoIterator<Item = usize>,
         V: IntoIterator<Item = bool>,
     {
-        std::iter::zip(var_indices.into_iter(), negates.into_iter())
+        std::iter::zip(var_indices, negates)
             .map(|(i, n)| Literal::new(i, n))
             .collect()
     }
@@ -53,6 +77,20 @@ impl Clause {
         self
     }
 
+    /// Returns the logical negation of the clause in CNF, that is one unit clause per negated literal.
+    /// Unlike `negated`, which negates each literal but keeps the disjunction.
+    pub fn logical_negation(&self) -> Vec<Clause> {
+        self.0
+            .iter()
+            .map(|elem| Self(vec![elem.negated()]))
+            .collect()
+    }
+
+    /// Returns a new clause with `f` applied to each literal.
+    pub fn map(&self, f: impl FnMut(Literal) -> Literal) -> Clause {
+        self.0.iter().copied().map(f).collect()
+    }
+
     /// Returns an iterator over the variables evaluated (that is, possibly negated)
     pub fn iter_eval<'a>(&'a self, vars: &'a BoolVec) -> impl Iterator<Item = bool> + 'a {
         self.0.iter().map(|elem| elem.eval_with(vars))
@@ -68,10 +106,237 @@ impl Clause {
         self.iter_eval(vars).any(|x| x)
     }
 
+    /// Returns whether the clause is satisfied by variable values packed in 64-bit words, see `Literal::eval_bits`.
+    pub fn test_sat_bits(&self, bits: &[u64]) -> bool {
+        self.0.iter().any(|elem| elem.eval_bits(bits))
+    }
+
+    /// Returns the number of literals of the clause satisfied by the given variable values, repeated literals included:
+    /// 0 if the clause is unsatisfied, 1 if it is critically satisfied (flipping the variable breaks it).
+    pub fn satisfied_count(&self, vars: &BoolVec) -> usize {
+        self.iter_eval(vars).filter(|x| *x).count()
+    }
+
+    /// Returns whether the clause contains a literal over the given variable, in either polarity.
+    pub fn contains_var(&self, var_index: usize) -> bool {
+        self.0.iter().any(|elem| elem.is_var(var_index))
+    }
+
+    /// Returns the set of the variables the clause refers to, in either polarity.
+    pub fn var_set(&self) -> BTreeSet<usize> {
+        self.0.iter().map(Literal::index).collect()
+    }
+
+    /// Returns whether a variable appears more than once in the clause, in either polarity.
+    pub fn has_repeated_var(&self) -> bool {
+        self.var_set().len() != self.0.len()
+    }
+
+    /// Returns the variables appearing more than once in the clause, in either polarity, in increasing order.
+    pub fn repeated_vars(&self) -> Vec<usize> {
+        let mut seen = HashSet::new();
+        let repeated: BTreeSet<_> = self
+            .0
+            .iter()
+            .map(Literal::index)
+            .filter(|&i| !seen.insert(i))
+            .collect();
+
+        repeated.into_iter().collect()
+    }
+
+    /// Returns whether the clause contains exactly `lit` (same variable and same polarity),
+    /// that is whether the clause is satisfied once `lit` is true.
+    pub fn is_satisfied_by(&self, lit: Literal) -> bool {
+        self.0.contains(&lit)
+    }
+
+    /// Returns the clause simplified once `lit` is true: `None` if the clause contains `lit` and is satisfied,
+    /// the clause without the occurrences of the negation of `lit` otherwise (unchanged if its variable is absent).
+    /// This conditions a single clause, see `Instance::simplify_under` for the whole instance.
+    pub fn condition(&self, lit: Literal) -> Option<Clause> {
+        if self.is_satisfied_by(lit) {
+            return None;
+        }
+
+        let negated = lit.negated();
+        Some(Self(
+            self.0
+                .iter()
+                .filter(|&&elem| elem != negated)
+                .copied()
+                .collect(),
+        ))
+    }
+
+    /// Returns whether the clause contains both a literal and its negation, and is thus always satisfied.
+    pub fn is_tautology(&self) -> bool {
+        self.0.iter().any(|elem| self.0.contains(&elem.negated()))
+    }
+
+    /// Returns the literal of the clause over `pivot` whose negation appears in `other`, if any.
+    fn pivot_literal(&self, other: &Clause, pivot: usize) -> Option<Literal> {
+        self.0
+            .iter()
+            .copied()
+            .find(|elem| elem.index() == pivot && other.0.contains(&elem.negated()))
+    }
+
+    /// Returns the resolvent of the two clauses on the `pivot` variable, that is the literals of both clauses
+    /// but the clashing pair over `pivot`, without duplicates.
+    /// Returns `None` if `pivot` does not appear positively in one clause and negated in the other.
+    /// The resolvent may be a tautology if the clauses clash on another variable,
+    /// or if one of them already contains both polarities of `pivot`.
+    pub fn resolve(&self, other: &Clause, pivot: usize) -> Option<Clause> {
+        let pivot_lit = self.pivot_literal(other, pivot)?;
+
+        let mut literals: Vec<_> = self
+            .0
+            .iter()
+            .filter(|elem| **elem != pivot_lit)
+            .chain(other.0.iter().filter(|elem| **elem != pivot_lit.negated()))
+            .copied()
+            .collect();
+        literals.sort();
+        literals.dedup();
+
+        Some(Self(literals))
+    }
+
+    /// Returns whether the resolvent of the two clauses on the `pivot` variable would be a tautology,
+    /// that is whether their literals but the clashing pair over `pivot` contain a literal and its negation.
+    /// Returns false if the clauses cannot be resolved on `pivot`, and allocates nothing.
+    pub fn resolvent_is_tautology(&self, other: &Clause, pivot: usize) -> bool {
+        let Some(pivot_lit) = self.pivot_literal(other, pivot) else {
+            return false;
+        };
+
+        let in_resolvent = |lit: &Literal| {
+            (*lit != pivot_lit && self.0.contains(lit))
+                || (*lit != pivot_lit.negated() && other.0.contains(lit))
+        };
+        self.0
+            .iter()
+            .chain(&other.0)
+            .filter(|elem| in_resolvent(elem))
+            .any(|elem| in_resolvent(&elem.negated()))
+    }
+
+    /// Returns the literal block distance (LBD) of the clause, that is the number of distinct decision levels
+    /// among its literals, given the decision level of each variable.
+    /// Panics if a variable of the clause has no level in `levels`.
+    pub fn lbd(&self, levels: &[usize]) -> usize {
+        self.0
+            .iter()
+            .map(|elem| levels[elem.index()])
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Removes the repeated literals of the clause, keeping the first occurrence of each.
+    pub fn dedup_literals(&mut self) {
+        let mut seen = HashSet::new();
+        self.0.retain(|elem| seen.insert(*elem));
+    }
+
+    /// Keeps only the literals for which `f` returns true, in order.
+    pub fn retain(&mut self, f: impl FnMut(&Literal) -> bool) {
+        self.0.retain(f);
+    }
+
+    /// Returns the canonical form of the clause, its distinct literals in increasing order,
+    /// so that clauses equal as sets of literals have equal canonical forms.
+    pub fn canonical(&self) -> Clause {
+        let mut literals = self.0.clone();
+        literals.sort();
+        literals.dedup();
+
+        Self(literals)
+    }
+
+    /// Evaluates the clause under a partial assignment: `True` if a literal is true,
+    /// `False` if all literals are false, `Unknown` otherwise.
+    /// Literals over variables not present in the assignment are unassigned.
+    pub fn evaluate(&self, a: &Assignment) -> ClauseValue {
+        let mut value = ClauseValue::False;
+
+        for elem in &self.0 {
+            match a.eval(*elem) {
+                Some(true) => return ClauseValue::True,
+                Some(false) => {}
+                None => value = ClauseValue::Unknown,
+            }
+        }
+
+        value
+    }
+
     /// Returns the Literals
     pub fn get_literals(&self) -> &[Literal] {
         &self.0
     }
+
+    /// Returns the number of bytes allocated on the heap for the literals.
+    pub fn heap_size(&self) -> usize {
+        self.0.capacity() * std::mem::size_of::<Literal>()
+    }
+
+    /// Shrinks the capacity of the literals as much as possible.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit();
+    }
+
+    /// Randomly permutes the literals.
+    #[cfg(feature = "rand")]
+    pub(crate) fn shuffle<R: rand::Rng>(&mut self, rng: &mut R) {
+        use rand::seq::SliceRandom;
+
+        self.0.shuffle(rng);
+    }
+
+    /// Returns the number of literals in the clause.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the width of the clause, the proof complexity term for its number of literals (same as `len`).
+    pub fn width(&self) -> usize {
+        self.len()
+    }
+
+    /// Returns whether the clause has no literals.
+    /// An empty clause can never be satisfied.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether the clause has exactly one literal.
+    pub fn is_unit(&self) -> bool {
+        self.0.len() == 1
+    }
+
+    /// Returns the sole literal of the clause if it is a unit clause, `None` otherwise.
+    pub fn unit_literal(&self) -> Option<Literal> {
+        match self.0.as_slice() {
+            [lit] => Some(*lit),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of positive (not negated) literals of the clause, repeated literals included.
+    pub fn positive_count(&self) -> usize {
+        self.0.iter().filter(|elem| !elem.is_negated()).count()
+    }
+
+    /// Returns whether the clause is a Horn clause, with at most one positive literal.
+    pub fn is_horn(&self) -> bool {
+        self.positive_count() <= 1
+    }
+
+    /// Returns whether all literals of the clause are negated (true for the empty clause).
+    pub fn is_negative(&self) -> bool {
+        self.positive_count() == 0
+    }
 }
 
 impl FromIterator<Literal> for Clause {
@@ -83,8 +348,17 @@ impl FromIterator<Literal> for Clause {
     }
 }
 
+/// Displays the clause in math notation, `(x0 ∨ ¬x1)`.
+/// The alternate flag `{:#}` displays it in CNF instead, `1 -2 0`.
 impl fmt::Display for Clause {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            for elem in &self.0 {
+                write!(f, "{} ", elem.as_cnf())?;
+            }
+            return write!(f, "0");
+        }
+
         write!(f, "(")?;
         for (i, elem) in self.0.iter().enumerate() {
             if i != 0 {
@@ -102,6 +376,7 @@ impl fmt::Display for Clause {
 }
 
 #[cfg(test)]
+#[allow(clippy::map_all_any_identity)]
 mod tests {
     use super::*;
     use bool_vec::BoolVec;
@@ -129,6 +404,14 @@ mod tests {
         let negated_clause = clause.negated();
 
         assert_eq!(negated_clause, Clause::from_cnf(vec![-1, 2, -3]));
+        assert_eq!(
+            clause.logical_negation(),
+            vec![
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![2]),
+                Clause::from_cnf(vec![-3])
+            ]
+        );
 
         let mut bv = BoolVec::from([false, false, false]);
         assert!(clause.test_sat(&bv));
@@ -137,10 +420,226 @@ mod tests {
         bv.set(2, true).unwrap();
         assert!(clause.test_sat(&bv));
 
+        assert_eq!(clause.satisfied_count(&bv), 1);
+        assert_eq!(
+            clause.satisfied_count(&BoolVec::from([true, false, true])),
+            3
+        );
+        assert_eq!(
+            clause.satisfied_count(&BoolVec::from([false, true, false])),
+            0
+        );
+        assert_eq!(Clause::from_cnf(vec![1, 1]).satisfied_count(&bv), 0);
+        assert_eq!(Clause::from_cnf(vec![3, 3]).satisfied_count(&bv), 2);
+
         assert!(
             std::iter::zip(clause.iter_eval(&bv), clause.iter_eval_negated(&bv))
                 .map(|(x, nx)| x != nx)
                 .all(|x| x)
         );
     }
+
+    #[test]
+    fn membership() {
+        let clause = Clause::from_cnf(vec![1, -2]);
+
+        assert!(clause.contains_var(0));
+        assert!(clause.contains_var(1));
+        assert!(!clause.contains_var(2));
+        assert_eq!(
+            Clause::from_cnf(vec![3, -1, -3]).var_set(),
+            BTreeSet::from([0, 2])
+        );
+
+        assert!(clause.is_satisfied_by(Literal::from_cnf(1)));
+        assert!(clause.is_satisfied_by(Literal::from_cnf(-2)));
+        assert!(!clause.is_satisfied_by(Literal::from_cnf(-1)));
+        assert!(!clause.is_satisfied_by(Literal::from_cnf(3)));
+    }
+
+    #[test]
+    fn condition() {
+        let clause = Clause::from_cnf(vec![1, -2, 3, -2]);
+
+        assert_eq!(clause.condition(Literal::from_cnf(3)), None);
+        assert_eq!(
+            clause.condition(Literal::from_cnf(2)),
+            Some(Clause::from_cnf(vec![1, 3]))
+        );
+        assert_eq!(clause.condition(Literal::from_cnf(4)), Some(clause.clone()));
+        assert_eq!(
+            Clause::from_cnf(vec![-1]).condition(Literal::from_cnf(1)),
+            Some(Clause::from_cnf(vec![]))
+        );
+    }
+
+    #[test]
+    fn resolution() {
+        let a = Clause::from_cnf(vec![1, -2, 3]);
+        let b = Clause::from_cnf(vec![2, 3, -4]);
+
+        assert_eq!(a.resolve(&b, 1), Some(Clause::from_cnf(vec![1, 3, -4])));
+        assert_eq!(b.resolve(&a, 1), a.resolve(&b, 1));
+        assert_eq!(a.resolve(&b, 2), None);
+        assert_eq!(a.resolve(&b, 0), None);
+
+        let unit = Clause::from_cnf(vec![1]);
+        let neg_unit = Clause::from_cnf(vec![-1]);
+        assert_eq!(unit.resolve(&neg_unit, 0), Some(Clause::from_cnf(vec![])));
+
+        assert!(!a.is_tautology());
+        assert!(Clause::from_cnf(vec![1, -1])
+            .resolve(&Clause::from_cnf(vec![-1, 2, -2]), 0)
+            .unwrap()
+            .is_tautology());
+
+        assert!(!a.resolvent_is_tautology(&b, 1));
+        assert!(
+            Clause::from_cnf(vec![1, -2]).resolvent_is_tautology(&Clause::from_cnf(vec![2, -1]), 1)
+        );
+        assert!(!unit.resolvent_is_tautology(&neg_unit, 0));
+
+        let clashing = Clause::from_cnf(vec![1, -1, 2]);
+        assert_eq!(
+            clashing.resolve(&neg_unit, 0),
+            Some(Clause::from_cnf(vec![-1, 2]))
+        );
+        assert!(!clashing.resolvent_is_tautology(&neg_unit, 0));
+        assert_eq!(
+            clashing.resolve(&unit, 0),
+            Some(Clause::from_cnf(vec![1, 2]))
+        );
+    }
+
+    #[test]
+    fn display() {
+        let clause = Clause::from_cnf(vec![1, -2]);
+
+        assert_eq!(format!("{clause}"), "(x0 ∨ ¬x1)");
+        assert_eq!(format!("{clause:#}"), "1 -2 0");
+        assert_eq!(format!("{:#}", Clause::from_cnf(vec![])), "0");
+    }
+
+    #[test]
+    fn lbd() {
+        let levels = [0, 2, 2, 1];
+
+        assert_eq!(Clause::from_cnf(vec![1, -2, 3, 4]).lbd(&levels), 3);
+        assert_eq!(Clause::from_cnf(vec![-2, 3]).lbd(&levels), 1);
+        assert_eq!(Clause::from_cnf(vec![]).lbd(&levels), 0);
+    }
+
+    #[test]
+    fn repeated_vars() {
+        let duplicate = Clause::from_cnf(vec![1, 1, 2]);
+        let tautology = Clause::from_cnf(vec![3, 2, -3, 2, 3]);
+        let clean = Clause::from_cnf(vec![1, -2]);
+
+        assert!(duplicate.has_repeated_var());
+        assert_eq!(duplicate.repeated_vars(), vec![0]);
+        assert!(tautology.has_repeated_var());
+        assert_eq!(tautology.repeated_vars(), vec![1, 2]);
+        assert!(!clean.has_repeated_var());
+        assert!(clean.repeated_vars().is_empty());
+
+        let mut tautology = tautology;
+        tautology.dedup_literals();
+        assert_eq!(tautology, Clause::from_cnf(vec![3, 2, -3]));
+    }
+
+    #[test]
+    fn map() {
+        let clause = Clause::from_cnf(vec![1, -2]);
+
+        assert_eq!(clause.map(|lit| -lit), clause.negated());
+        assert_eq!(
+            clause.map(|lit| Literal::new(lit.index() + 2, lit.is_negated())),
+            Clause::from_cnf(vec![3, -4])
+        );
+    }
+
+    #[test]
+    fn evaluate() {
+        let clause = Clause::from_cnf(vec![1, -2]);
+        let mut assignment = Assignment::new(2);
+
+        assert_eq!(clause.evaluate(&assignment), ClauseValue::Unknown);
+        assignment.set(0, false).unwrap();
+        assert_eq!(clause.evaluate(&assignment), ClauseValue::Unknown);
+        assignment.set(1, true).unwrap();
+        assert_eq!(clause.evaluate(&assignment), ClauseValue::False);
+        assignment.set(1, false).unwrap();
+        assert_eq!(clause.evaluate(&assignment), ClauseValue::True);
+
+        assert_eq!(
+            Clause::from_cnf(vec![]).evaluate(&assignment),
+            ClauseValue::False
+        );
+    }
+
+    #[test]
+    fn unit() {
+        let empty = Clause::from_cnf(vec![]);
+        let unit = Clause::from_cnf(vec![-3]);
+        let binary = Clause::from_cnf(vec![1, 2]);
+
+        assert!(empty.is_empty());
+        assert!(!empty.is_unit());
+        assert_eq!(empty.unit_literal(), None);
+
+        assert!(!unit.is_empty());
+        assert!(unit.is_unit());
+        assert_eq!(unit.unit_literal(), Some(Literal::from_cnf(-3)));
+
+        assert_eq!(binary.len(), 2);
+        assert_eq!(binary.width(), 2);
+        assert_eq!(binary.unit_literal(), None);
+    }
+
+    #[test]
+    fn retain() {
+        let mut clause = Clause::from_cnf(vec![1, -2, 3, -4]);
+        clause.retain(|lit| !lit.is_negated());
+
+        assert_eq!(clause, Clause::from_cnf(vec![1, 3]));
+    }
+
+    #[test]
+    fn canonical() {
+        assert_eq!(
+            Clause::from_cnf(vec![3, -1, 3, 2]).canonical(),
+            Clause::from_cnf(vec![-1, 2, 3])
+        );
+        assert_eq!(
+            Clause::from_cnf(vec![2, 1]).canonical(),
+            Clause::from_cnf(vec![1, 2, 1]).canonical()
+        );
+    }
+
+    #[test]
+    fn polarity() {
+        let negative = Clause::from_cnf(vec![-1, -2]);
+        let horn = Clause::from_cnf(vec![-1, 2, -3]);
+        let positive = Clause::from_cnf(vec![1, 2, -3]);
+
+        assert_eq!(negative.positive_count(), 0);
+        assert!(negative.is_horn());
+        assert!(negative.is_negative());
+
+        assert_eq!(horn.positive_count(), 1);
+        assert!(horn.is_horn());
+        assert!(!horn.is_negative());
+
+        assert_eq!(positive.positive_count(), 2);
+        assert!(!positive.is_horn());
+        assert!(Clause::from_cnf(vec![]).is_negative());
+    }
+
+    #[test]
+    fn heap_size() {
+        let clause = Clause::from_cnf(vec![1, -2, 3]);
+
+        assert!(clause.heap_size() >= 3 * std::mem::size_of::<Literal>());
+        assert_eq!(Clause::from_cnf(vec![]).heap_size(), 0);
+    }
 }
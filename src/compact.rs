@@ -0,0 +1,118 @@
+use crate::{instance::Instance, literal::Literal};
+
+use bool_vec::BoolVec;
+
+/// The clauses of an instance stored contiguously, for cache-friendly evaluation of large instances:
+/// the literals of all clauses in a single pool, and the offset at which each clause starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactClauses {
+    generation: u64,
+    literals: Vec<Literal>,
+    /// Start of each clause in `literals`, followed by the total number of literals.
+    offsets: Vec<usize>,
+}
+
+impl CompactClauses {
+    /// Copies the clauses of the instance into the compact form.
+    pub fn new(instance: &Instance) -> Self {
+        let mut literals = Vec::with_capacity(instance.num_literals());
+        let mut offsets = Vec::with_capacity(instance.get_clauses().len() + 1);
+
+        offsets.push(0);
+        for clause in instance.clauses() {
+            literals.extend_from_slice(clause.get_literals());
+            offsets.push(literals.len());
+        }
+
+        Self {
+            generation: instance.generation(),
+            literals,
+            offsets,
+        }
+    }
+
+    /// Returns whether the compact form is up to date with the instance,
+    /// that is whether the instance has not been mutated since it was built.
+    pub fn is_valid(&self, instance: &Instance) -> bool {
+        self.generation == instance.generation()
+    }
+
+    /// Returns the number of clauses.
+    pub fn num_clauses(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the total number of literals of the clauses.
+    pub fn num_literals(&self) -> usize {
+        self.literals.len()
+    }
+
+    /// Returns the literals of the clause at `index`.
+    /// Panics if `index` is out of bounds.
+    pub fn clause(&self, index: usize) -> &[Literal] {
+        &self.literals[self.offsets[index]..self.offsets[index + 1]]
+    }
+
+    /// Returns an iterator over the literals of each clause.
+    pub fn iter(&self) -> impl Iterator<Item = &[Literal]> {
+        self.offsets
+            .windows(2)
+            .map(|window| &self.literals[window[0]..window[1]])
+    }
+
+    /// Returns the number of clauses satisfied by the given variable values.
+    /// Panics if a literal is not present in the variables.
+    pub fn count_sat(&self, vars: &BoolVec) -> usize {
+        self.iter()
+            .filter(|literals| literals.iter().any(|lit| lit.eval_with(vars)))
+            .count()
+    }
+
+    /// Returns whether all clauses are satisfied by the given variable values.
+    /// Panics if a literal is not present in the variables.
+    pub fn is_sat(&self, vars: &BoolVec) -> bool {
+        self.iter()
+            .all(|literals| literals.iter().any(|lit| lit.eval_with(vars)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clause::Clause;
+
+    #[test]
+    fn compact() {
+        let mut instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![]),
+                Clause::from_cnf(vec![2, 3, -1]),
+            ],
+        );
+        let compact = instance.compact();
+
+        assert_eq!(compact.num_clauses(), 3);
+        assert_eq!(compact.num_literals(), 5);
+        assert!(compact.clause(1).is_empty());
+        assert_eq!(
+            compact.iter().collect::<Vec<_>>(),
+            instance
+                .clauses()
+                .map(Clause::get_literals)
+                .collect::<Vec<_>>()
+        );
+
+        for x in 0..8 {
+            let vars = BoolVec::from([x & 1 != 0, x & 2 != 0, x & 4 != 0]);
+            instance.vars = BoolVec::from(vars.into_vec());
+            assert_eq!(compact.count_sat(&vars), instance.count_sat());
+            assert!(!compact.is_sat(&vars));
+        }
+
+        assert!(compact.is_valid(&instance));
+        instance.add_clause(Clause::from_cnf(vec![1]));
+        assert!(!compact.is_valid(&instance));
+    }
+}
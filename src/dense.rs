@@ -0,0 +1,138 @@
+use crate::instance::Instance;
+
+use bool_vec::BoolVec;
+
+/// A compiled form of an instance with at most 64 variables, evaluated with bitwise operations.
+/// An assignment is packed in a `u64` where bit `i` holds the value of the variable `i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DenseInstance {
+    generation: u64,
+    num_vars: usize,
+    /// For each clause, the mask of its positive literals and the mask of its negated literals.
+    masks: Vec<(u64, u64)>,
+}
+
+impl DenseInstance {
+    /// Maximum number of variables supported by the dense representation.
+    pub const MAX_VARS: usize = u64::BITS as usize;
+
+    /// Compiles the instance, returns `None` if it has more than `MAX_VARS` variables
+    /// or if a literal refers to a variable outside of a `u64`.
+    pub fn compile(instance: &Instance) -> Option<Self> {
+        if instance.num_vars() > Self::MAX_VARS {
+            return None;
+        }
+
+        let masks = instance
+            .get_clauses()
+            .iter()
+            .map(|clause| {
+                clause
+                    .get_literals()
+                    .iter()
+                    .try_fold((0, 0), |(pos, neg), lit| {
+                        let bit = 1u64.checked_shl(lit.index().try_into().ok()?)?;
+                        Some(if lit.is_negated() {
+                            (pos, neg | bit)
+                        } else {
+                            (pos | bit, neg)
+                        })
+                    })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            generation: instance.generation(),
+            num_vars: instance.num_vars(),
+            masks,
+        })
+    }
+
+    /// Packs the variables of the instance in a `u64`, bit `i` holding the value of the variable `i`.
+    /// Returns `None` if there are more than `MAX_VARS` variables.
+    pub fn pack(vars: &BoolVec) -> Option<u64> {
+        if vars.len() > Self::MAX_VARS {
+            return None;
+        }
+
+        Some(
+            vars.into_iter()
+                .enumerate()
+                .fold(0, |acc, (i, v)| acc | (u64::from(v) << i)),
+        )
+    }
+
+    /// Returns whether the compiled form is up to date with the instance,
+    /// that is whether the instance has not been mutated since it was compiled.
+    pub fn is_valid(&self, instance: &Instance) -> bool {
+        self.generation == instance.generation()
+    }
+
+    /// Returns the number of variables of the compiled instance.
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Returns the number of clauses of the compiled instance.
+    pub fn num_clauses(&self) -> usize {
+        self.masks.len()
+    }
+
+    /// Returns the number of clauses satisfied by the packed assignment.
+    pub fn count_sat(&self, assignment: u64) -> usize {
+        self.masks
+            .iter()
+            .filter(|(pos, neg)| (assignment & pos) | (!assignment & neg) != 0)
+            .count()
+    }
+
+    /// Returns true if all clauses are satisfied by the packed assignment.
+    pub fn is_sat(&self, assignment: u64) -> bool {
+        self.masks
+            .iter()
+            .all(|(pos, neg)| (assignment & pos) | (!assignment & neg) != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clause::Clause;
+
+    #[test]
+    fn matches_instance() {
+        let mut instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, -2, 3]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![-3]),
+                Clause::from_cnf(vec![]),
+            ],
+        );
+        let dense = instance.compile_dense().unwrap();
+        assert_eq!(dense.num_clauses(), 4);
+        assert!(dense.is_valid(&instance));
+
+        for assignment in 0..8u64 {
+            instance.vars = BoolVec::from([
+                assignment & 1 != 0,
+                assignment & 2 != 0,
+                assignment & 4 != 0,
+            ]);
+
+            assert_eq!(DenseInstance::pack(&instance.vars), Some(assignment));
+            assert_eq!(dense.count_sat(assignment), instance.count_sat());
+            assert!(!dense.is_sat(assignment));
+        }
+    }
+
+    #[test]
+    fn too_many_vars() {
+        assert!(Instance::with_clauses(64, vec![]).compile_dense().is_some());
+        assert!(Instance::with_clauses(65, vec![]).compile_dense().is_none());
+        assert!(Instance::with_clauses(2, vec![Clause::from_cnf(vec![65])])
+            .compile_dense()
+            .is_none());
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use bool_vec::boolvec;
+
+use crate::{instance::Instance, literal::Literal, solver::SolveResult};
+
+impl Instance {
+    /// Solves the instance with an external solver binary following the SAT competition conventions,
+    /// such as kissat or cadical.
+    /// The instance is written to a temporary CNF file, removed once the solver exits, and given as the solver's only argument.
+    /// The result is read from the `s` line of its output, and the model from its `v` lines.
+    /// Without an `s` line, the exit codes 10 (SAT) and 20 (UNSAT) are used, and anything else is `Unknown`.
+    pub fn solve_external(&self, solver_path: &str) -> io::Result<SolveResult> {
+        let path = temp_cnf_path();
+        let output = self
+            .to_file(&path)
+            .and_then(|_| Command::new(solver_path).arg(&path).output());
+        let _ = fs::remove_file(&path);
+        let output = output?;
+
+        Ok(parse_solver_output(
+            &String::from_utf8_lossy(&output.stdout),
+            output.status.code(),
+            self.num_vars(),
+        ))
+    }
+}
+
+/// Returns a path in the temporary directory that is unique for this process.
+fn temp_cnf_path() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    std::env::temp_dir().join(format!(
+        "sat_lab_{}_{}.cnf",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Parses the competition output of a solver, see `Instance::solve_external`.
+fn parse_solver_output(output: &str, exit_code: Option<i32>, num_vars: usize) -> SolveResult {
+    let mut status = None;
+    let mut model = boolvec![false; num_vars];
+
+    for line in output.lines() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("s") => status = tokens.next(),
+            Some("v") => {
+                for lit in tokens
+                    .filter_map(|token| token.parse().ok())
+                    .take_while(|&cnf| cnf != 0)
+                    .map(Literal::from_cnf)
+                {
+                    model.set(lit.index(), !lit.is_negated());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match (status, exit_code) {
+        (Some("SATISFIABLE"), _) | (None, Some(10)) => SolveResult::Sat(model),
+        (Some("UNSATISFIABLE"), _) | (None, Some(20)) => SolveResult::Unsat,
+        _ => SolveResult::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bool_vec::BoolVec;
+
+    #[test]
+    fn output() {
+        let sat = "c banner\ns SATISFIABLE\nv 1 -2\nv 3 0\n";
+        assert_eq!(
+            parse_solver_output(sat, Some(10), 4),
+            SolveResult::Sat(BoolVec::from([true, false, true, false]))
+        );
+
+        assert_eq!(
+            parse_solver_output("s UNSATISFIABLE\n", Some(20), 4),
+            SolveResult::Unsat
+        );
+        assert_eq!(
+            parse_solver_output("s UNKNOWN\n", Some(0), 4),
+            SolveResult::Unknown
+        );
+        assert_eq!(parse_solver_output("", Some(20), 4), SolveResult::Unsat);
+        assert_eq!(parse_solver_output("", None, 4), SolveResult::Unknown);
+    }
+
+    #[test]
+    fn competition_output_roundtrip() {
+        for result in [
+            SolveResult::Sat(BoolVec::from([false, true, true])),
+            SolveResult::Unsat,
+            SolveResult::Unknown,
+        ] {
+            let mut out = vec![];
+            result.to_competition_output(&mut out, 3).unwrap();
+            let output = String::from_utf8(out).unwrap();
+            assert_eq!(parse_solver_output(&output, None, 3), result);
+        }
+    }
+
+    #[test]
+    fn missing_solver() {
+        let instance = Instance::with_clauses(1, vec![]);
+
+        assert!(instance
+            .solve_external("/nonexistent/sat_lab/solver")
+            .is_err());
+    }
+}
@@ -1,17 +1,59 @@
-use crate::{clause::Clause, literal::Literal};
+use crate::{
+    assignment::Assignment,
+    clause::{Clause, ClauseKind, ClauseValue},
+    compact::CompactClauses,
+    dense::DenseInstance,
+    literal::Literal,
+    occurrence::{OccurrenceIndex, VarPolarity},
+    parse::{
+        clause_lines, ends_clause, parse_clause, parse_clause_tokens, parse_header,
+        parse_labeled_clause, parse_quantifier, referred_vars, CnfParseError, ParseOptions,
+        ParseWarning,
+    },
+    quantifier::Quantifier,
+    union_find::UnionFind,
+    write::{write_clause, WriteOptions},
+};
 
 use bool_vec::{boolvec, BoolVec};
 
 #[cfg(feature = "rand")]
 use rand::{distributions::Standard, Rng, SeedableRng};
 
-use std::{fs, io::Write, path::Path};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 /// A SAT instance
 #[derive(Debug)]
 pub struct Instance {
+    /// Current assignment of the variables.
+    /// Prefer `model` and `set_model`, direct access may be deprecated in a future version.
     pub vars: BoolVec,
     clauses: Vec<Clause>,
+    /// QBF quantifier prefix, empty for plain SAT.
+    quantifiers: Vec<(Quantifier, Vec<usize>)>,
+    /// Identifies the state of the instance, changed by every mutation, see `generation`.
+    generation: u64,
+    /// Canonical forms of the clauses for `add_clause_dedup`, with the generation they were collected at.
+    canonical_clauses: Option<(u64, HashSet<Clause>)>,
+    /// Labels of the clauses, parallel to `clauses`, see `clause_label`. Empty when no clause is labeled.
+    labels: Vec<Option<String>>,
+    /// Kinds of the clauses, parallel to `clauses`, see `clause_kind`. Empty when no clause is learned.
+    kinds: Vec<ClauseKind>,
+    /// Variables the eliminations must keep, see `freeze_var`.
+    frozen: HashSet<usize>,
+}
+
+/// Source of the generations, shared by all instances so that no two unrelated states share one.
+static GENERATIONS: AtomicU64 = AtomicU64::new(0);
+
+fn next_generation() -> u64 {
+    GENERATIONS.fetch_add(1, Ordering::Relaxed)
 }
 
 impl Clone for Instance {
@@ -24,6 +66,12 @@ impl Clone for Instance {
         Self {
             vars: bvec_clone,
             clauses: self.clauses.clone(),
+            quantifiers: self.quantifiers.clone(),
+            generation: self.generation,
+            canonical_clauses: self.canonical_clauses.clone(),
+            labels: self.labels.clone(),
+            kinds: self.kinds.clone(),
+            frozen: self.frozen.clone(),
         }
     }
 }
@@ -31,15 +79,34 @@ impl Clone for Instance {
 impl Instance {
     /// Creates a new instance with the given variables and clauses.
     pub fn new(vars: BoolVec, clauses: Vec<Clause>) -> Self {
-        Self { vars, clauses }
+        Self {
+            vars,
+            clauses,
+            quantifiers: vec![],
+            generation: next_generation(),
+            canonical_clauses: None,
+            labels: vec![],
+            kinds: vec![],
+            frozen: HashSet::new(),
+        }
     }
 
     /// Creates a new instance with the given number of variables initialized at 0 and clauses.
     pub fn with_clauses(n: usize, clauses: Vec<Clause>) -> Self {
-        Self {
-            vars: boolvec![false; n],
-            clauses,
-        }
+        Self::new(boolvec![false; n], clauses)
+    }
+
+    /// Creates a new instance with the given number of variables initialized at 0 and no clauses,
+    /// with room for at least `clause_cap` clauses before reallocating.
+    pub fn with_capacity(n: usize, clause_cap: usize) -> Self {
+        Self::with_clauses(n, Vec::with_capacity(clause_cap))
+    }
+
+    /// Creates a new instance from the given clauses, with as many variables initialized at 0 as the clauses refer to,
+    /// that is the largest variable index plus one. Variables above it, appearing in no clause, are not counted.
+    pub fn from_clauses(clauses: impl IntoIterator<Item = Clause>) -> Self {
+        let clauses: Vec<_> = clauses.into_iter().collect();
+        Self::with_clauses(referred_vars(&clauses), clauses)
     }
 
     /// Creates a new instance from a file in Conjunctive Normal Form.
@@ -48,51 +115,270 @@ impl Instance {
     where
         P: AsRef<Path>,
     {
+        Self::from_reader(fs::File::open(path)?)
+    }
+
+    /// Reads the header `p cnf n m` of a file in Conjunctive Normal Form and returns the declared `(n, m)`,
+    /// without reading past it, to catalogue files quickly.
+    /// Returns an error if the file cannot be read or the header is missing or malformed.
+    pub fn read_header<P: AsRef<Path>>(path: P) -> Result<(usize, usize), CnfParseError> {
+        read_header_from(&mut BufReader::new(fs::File::open(path)?))
+    }
+
+    /// Reads the header of a reader in Conjunctive Normal Form, and returns the declared `(n, m)`
+    /// along with an iterator parsing the following clauses one at a time, without holding them all in memory.
+    /// Comment and blank lines between the clauses are skipped, and the quantifier lines of QDIMACS are not supported.
+    /// Returns an error if the header is missing or malformed, and the iterator yields an error for each malformed clause.
+    pub fn stream_clauses<R: BufRead>(
+        mut reader: R,
+    ) -> std::io::Result<(
+        (usize, usize),
+        impl Iterator<Item = std::io::Result<Clause>>,
+    )> {
+        let header = read_header_from(&mut reader)?;
+        let options = ParseOptions::default();
+        let comment_char = options.comment_char;
+
+        let mut lines = reader.lines().filter(move |line| {
+            line.as_ref().map_or(true, |line| {
+                !line.trim().is_empty() && !line.starts_with(comment_char)
+            })
+        });
+        let clauses = std::iter::from_fn(move || {
+            let mut clause = String::new();
+            loop {
+                let line = match lines.next() {
+                    Some(Ok(line)) => line,
+                    Some(Err(error)) => return Some(Err(error)),
+                    None if clause.is_empty() => return None,
+                    None => break,
+                };
+                clause.push_str(&line);
+                clause.push(' ');
+                if ends_clause(&line, &options) {
+                    break;
+                }
+            }
+            Some(parse_clause(&clause, &options).ok_or(std::io::ErrorKind::InvalidInput.into()))
+        });
+
+        Ok((header, clauses))
+    }
+
+    /// Creates a new instance from a reader in Conjunctive Normal Form.
+    /// Returns an error if the content is not in CNF or is malformed.
+    pub fn from_reader<R: Read>(reader: R) -> std::io::Result<Self> {
+        Self::from_reader_with(reader, &ParseOptions::default())
+    }
+
+    /// Creates a new instance from a reader in a CNF dialect described by `options`.
+    /// A QDIMACS quantifier prefix (`e`/`a` lines between the header and the clauses) is kept in `quantifiers`,
+    /// and with `options.clause_labels` the clause annotations are kept as labels (see `clause_label`).
+    /// Returns an error if the content does not follow the dialect or is malformed,
+    /// or if a clause or a quantifier refers to an undeclared variable without `options.grow_vars`.
+    pub fn from_reader_with<R: Read>(reader: R, options: &ParseOptions) -> std::io::Result<Self> {
+        Self::from_reader_with_warnings(reader, options).map(|(instance, _)| instance)
+    }
+
+    /// Creates a new instance from a reader in a CNF dialect described by `options`, see `from_reader_with`.
+    /// Returns the instance along with a warning for each repair allowed by `options`,
+    /// that is a `ParseWarning::VarCount` if the variable count was grown with `options.grow_vars`.
+    pub fn from_reader_with_warnings<R: Read>(
+        mut reader: R,
+        options: &ParseOptions,
+    ) -> std::io::Result<(Self, Vec<ParseWarning>)> {
         // TODO: Custom errors
 
-        let content = fs::read_to_string(path)?;
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
 
-        let mut lines = content.trim().lines().skip_while(|x| x.starts_with('c'));
+        let mut lines = content.trim().lines().peekable();
+        let (n, m) = parse_header(&mut lines, options).ok_or(std::io::ErrorKind::InvalidInput)?;
 
-        let mut param_line = lines
-            .next()
+        let mut quantifiers = vec![];
+        while let Some(line) = lines.next_if(|line| line.starts_with(['e', 'a'])) {
+            quantifiers
+                .push(parse_quantifier(line, options).ok_or(std::io::ErrorKind::InvalidInput)?);
+        }
+
+        let (clauses, labels): (Vec<_>, Vec<_>) = clause_lines(lines.enumerate(), options)
+            .take(m)
+            .map(|(_, clause)| parse_labeled_clause(&clause, options))
+            .collect::<Option<Vec<_>>>()
             .ok_or(std::io::ErrorKind::InvalidInput)?
-            .split_whitespace()
-            .skip(1);
+            .into_iter()
+            .unzip();
+
+        let mut warnings = vec![];
+        let found_n = referred_vars(&clauses);
+        if found_n > n {
+            if !options.grow_vars {
+                return Err(std::io::ErrorKind::InvalidInput.into());
+            }
+            warnings.push(ParseWarning::VarCount {
+                declared: n,
+                found: found_n,
+            });
+        }
 
-        let problem_type = param_line.next().ok_or(std::io::ErrorKind::InvalidInput)?;
-        if problem_type != "cnf" {
+        let n = n.max(found_n);
+        if quantifiers
+            .iter()
+            .flat_map(|(_, vars)| vars)
+            .any(|&var| var >= n)
+        {
             return Err(std::io::ErrorKind::InvalidInput.into());
         }
 
-        let n = param_line
-            .next()
-            .ok_or(std::io::ErrorKind::InvalidInput)?
-            .parse()
-            .map_err(|_| std::io::ErrorKind::InvalidInput)?;
-        let m = param_line
-            .next()
-            .ok_or(std::io::ErrorKind::InvalidInput)?
-            .parse()
-            .map_err(|_| std::io::ErrorKind::InvalidInput)?;
+        let mut instance = Self::with_clauses(n, clauses);
+        instance.set_quantifiers(quantifiers);
+        if labels.iter().any(Option::is_some) {
+            instance.labels = labels;
+        }
+        Ok((instance, warnings))
+    }
 
-        let clauses = lines
-            .take(m)
-            .map(str::split_whitespace)
-            .map(|clause| {
-                clause
-                    .map(|x| x.parse())
-                    .take_while(|r| r.as_ref().map_or(false, |x| *x != 0))
-                    .map(|r| r.map(Literal::from_cnf))
-                    .collect::<Result<Clause, _>>()
-            })
-            .collect::<Result<_, _>>()
-            .map_err(|_| std::io::ErrorKind::InvalidInput)?;
+    /// Creates a new instance from a file in Conjunctive Normal Form, collecting every error.
+    /// See `from_reader_collecting`.
+    pub fn from_file_collecting<P>(path: P) -> Result<Self, Vec<CnfParseError>>
+    where
+        P: AsRef<Path>,
+    {
+        let file = fs::File::open(path).map_err(|error| vec![error.into()])?;
+        Self::from_reader_collecting(file)
+    }
 
-        Ok(Self {
-            vars: boolvec![false; n],
-            clauses,
-        })
+    /// Creates a new instance from a reader in Conjunctive Normal Form, reading past the malformed quantifier
+    /// and clause lines instead of stopping at the first one, to report all of them at once.
+    /// Returns the instance if no error occurred, or every error in line order otherwise.
+    /// Like `from_reader`, a missing or malformed header stops the parsing. Blank lines are skipped.
+    pub fn from_reader_collecting<R: Read>(mut reader: R) -> Result<Self, Vec<CnfParseError>> {
+        let options = ParseOptions::default();
+
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .map_err(|error| vec![error.into()])?;
+
+        // Numbered before skipping the blank lines, so that the errors point at the right line
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .peekable();
+        let (n, m) = parse_header(&mut lines.by_ref().map(|(_, line)| line), &options)
+            .ok_or_else(|| vec![CnfParseError::Header])?;
+
+        let mut errors = vec![];
+        let mut quantifiers = vec![];
+        while let Some((i, line)) = lines.next_if(|(_, line)| line.starts_with(['e', 'a'])) {
+            match parse_quantifier(line, &options) {
+                Some((_, vars)) if vars.iter().any(|&var| var >= n) => {
+                    errors.push(CnfParseError::UndeclaredVar { line: i + 1 })
+                }
+                Some(quantifier) => quantifiers.push(quantifier),
+                None => errors.push(CnfParseError::Quantifier { line: i + 1 }),
+            }
+        }
+
+        let mut clauses = vec![];
+        for (i, clause) in clause_lines(lines, &options).take(m) {
+            match parse_clause(&clause, &options) {
+                Some(clause) if referred_vars(std::slice::from_ref(&clause)) > n => {
+                    errors.push(CnfParseError::UndeclaredVar { line: i + 1 })
+                }
+                Some(clause) => clauses.push(clause),
+                None => errors.push(CnfParseError::Clause { line: i + 1 }),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut instance = Self::with_clauses(n, clauses);
+        instance.set_quantifiers(quantifiers);
+        Ok(instance)
+    }
+
+    /// Creates a new instance from a file in Conjunctive Normal Form, repairing minor defects.
+    /// See `from_reader_lossy`.
+    pub fn from_file_lossy<P>(path: P) -> std::io::Result<(Self, Vec<ParseWarning>)>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_reader_lossy(fs::File::open(path)?)
+    }
+
+    /// Creates a new instance from a reader in Conjunctive Normal Form, repairing minor defects:
+    /// blank lines are ignored, the end of the content terminates an unterminated last clause,
+    /// and the variable and clause counts are recomputed from the content.
+    /// A QDIMACS quantifier prefix is kept in `quantifiers`, as by `from_reader`.
+    /// Returns the instance along with a warning for each repair.
+    /// Returns an error if the header is missing or a literal is malformed.
+    pub fn from_reader_lossy<R: Read>(mut reader: R) -> std::io::Result<(Self, Vec<ParseWarning>)> {
+        let options = ParseOptions::default();
+
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        let mut lines = content.lines().enumerate();
+        let (n, m) = parse_header(&mut lines.by_ref().map(|(_, line)| line), &options)
+            .ok_or(std::io::ErrorKind::InvalidInput)?;
+
+        let mut blank_lines = vec![];
+        let lines = lines.filter(|(i, line)| {
+            if line.trim().is_empty() {
+                blank_lines.push(ParseWarning::BlankLine { line: i + 1 });
+            }
+            !line.trim().is_empty() && !line.starts_with(options.comment_char)
+        });
+        let mut lines = lines.peekable();
+
+        let mut quantifiers = vec![];
+        while let Some((_, line)) = lines.next_if(|(_, line)| line.starts_with(['e', 'a'])) {
+            quantifiers
+                .push(parse_quantifier(line, &options).ok_or(std::io::ErrorKind::InvalidInput)?);
+        }
+
+        let mut unterminated = vec![];
+        let mut clauses = vec![];
+        for (i, line) in clause_lines(lines, &options) {
+            let (clause, terminated) =
+                parse_clause_tokens(&line, options.base).ok_or(std::io::ErrorKind::InvalidInput)?;
+            if !terminated {
+                unterminated.push(ParseWarning::MissingTerminator { line: i + 1 });
+            }
+            clauses.push(clause);
+        }
+
+        let mut warnings = blank_lines;
+        warnings.append(&mut unterminated);
+
+        if clauses.len() != m {
+            warnings.push(ParseWarning::ClauseCount {
+                declared: m,
+                found: clauses.len(),
+            });
+        }
+
+        let quantified_n = quantifiers
+            .iter()
+            .flat_map(|(_, vars)| vars)
+            .map(|var| var + 1)
+            .max()
+            .unwrap_or(0);
+        let found_n = referred_vars(&clauses).max(quantified_n);
+        if found_n > n {
+            warnings.push(ParseWarning::VarCount {
+                declared: n,
+                found: found_n,
+            });
+        }
+
+        let mut instance = Self::with_clauses(n.max(found_n), clauses);
+        instance.set_quantifiers(quantifiers);
+        Ok((instance, warnings))
     }
 
     /// Creates a new random instance with the given number of variables, clauses, and clause length.
@@ -108,14 +394,14 @@ impl Instance {
                 let mut var_indices: Vec<usize> = vec![0; k];
                 let mut negates = boolvec![false; k];
 
-                for i in 0..k {
+                for (i, var_index) in var_indices.iter_mut().enumerate() {
                     let mut idx = rng.gen_range(0..n);
                     while chosen_indices.contains(&idx) {
                         idx = rng.gen_range(0..n);
                     }
                     chosen_indices.push(idx);
 
-                    var_indices[i] = idx;
+                    *var_index = idx;
                     negates.set(i, rng.gen());
                 }
                 chosen_indices.clear();
@@ -124,7 +410,107 @@ impl Instance {
             })
             .collect();
 
-        Self { vars, clauses }
+        Self::new(vars, clauses)
+    }
+
+    /// Creates a new random instance for fuzzing, with the given number of variables and clauses,
+    /// and clause lengths drawn uniformly up to `max_k`.
+    /// Clauses may repeat a variable if `allow_dup` is set (otherwise their length is capped by `n`),
+    /// and may be empty if `allow_empty` is set.
+    /// The instance only depends on the state of `rng`, so a seeded RNG makes it reproducible.
+    /// Panics if clauses cannot be non-empty without `allow_empty`, that is if `n` or `max_k` is 0 while `m > 0`.
+    #[cfg(feature = "rand")]
+    pub fn new_fuzz<R: Rng>(
+        n: usize,
+        m: usize,
+        max_k: usize,
+        rng: &mut R,
+        allow_dup: bool,
+        allow_empty: bool,
+    ) -> Self {
+        assert!(
+            allow_empty || m == 0 || (n > 0 && max_k > 0),
+            "non-empty clauses over {n} variables of at most {max_k} literals"
+        );
+
+        let vars = BoolVec::from(
+            (&mut *rng)
+                .sample_iter(Standard)
+                .take(n)
+                .collect::<Vec<_>>(),
+        );
+
+        let max_k = if allow_dup { max_k } else { max_k.min(n) };
+        let max_k = if n == 0 { 0 } else { max_k };
+        let min_k = if allow_empty { 0 } else { max_k.min(1) };
+
+        let clauses = (0..m)
+            .map(|_| {
+                let k = rng.gen_range(min_k..=max_k);
+                let mut var_indices: Vec<usize> = Vec::with_capacity(k);
+
+                while var_indices.len() < k {
+                    let idx = rng.gen_range(0..n);
+                    if allow_dup || !var_indices.contains(&idx) {
+                        var_indices.push(idx);
+                    }
+                }
+
+                let negates: Vec<bool> = (&mut *rng).sample_iter(Standard).take(k).collect();
+                Clause::from_indices(var_indices, negates)
+            })
+            .collect();
+
+        Self::new(vars, clauses)
+    }
+
+    /// Creates a new random instance with community structure, as found in industrial instances:
+    /// the `communities × vars_per_community` variables are split in consecutive blocks, the communities,
+    /// and each of the `m` clauses of `k` distinct variables draws them from a single random community,
+    /// or from all variables with probability `inter_community_prob`.
+    /// The instance only depends on the state of `rng`, so a seeded RNG makes it reproducible.
+    /// Panics if `k` exceeds `vars_per_community`, if there is no community while `m > 0`,
+    /// or if `inter_community_prob` is not in `[0, 1]`.
+    #[cfg(feature = "rand")]
+    pub fn new_community<R: Rng>(
+        communities: usize,
+        vars_per_community: usize,
+        m: usize,
+        k: usize,
+        inter_community_prob: f64,
+        rng: &mut R,
+    ) -> Self {
+        assert!(
+            k <= vars_per_community,
+            "clauses of {k} variables in communities of {vars_per_community}"
+        );
+
+        let n = communities * vars_per_community;
+        let vars = BoolVec::from(
+            (&mut *rng)
+                .sample_iter(Standard)
+                .take(n)
+                .collect::<Vec<_>>(),
+        );
+
+        let clauses = (0..m)
+            .map(|_| {
+                let var_indices: Vec<_> = if rng.gen_bool(inter_community_prob) {
+                    rand::seq::index::sample(rng, n, k).into_vec()
+                } else {
+                    let offset = rng.gen_range(0..communities) * vars_per_community;
+                    rand::seq::index::sample(rng, vars_per_community, k)
+                        .into_iter()
+                        .map(|i| offset + i)
+                        .collect()
+                };
+
+                let negates: Vec<bool> = (&mut *rng).sample_iter(Standard).take(k).collect();
+                Clause::from_indices(var_indices, negates)
+            })
+            .collect();
+
+        Self::new(vars, clauses)
     }
 
     /// Save the instance to a file in Conjunctive Normal Form.
@@ -133,13 +519,121 @@ impl Instance {
         P: AsRef<Path>,
     {
         let mut file = fs::File::create(path)?;
+        self.to_writer(&mut file)
+    }
+
+    /// Save the instance to a file in Conjunctive Normal Form, preceded by the given comments,
+    /// such as the generator and seed it comes from. Each line of a comment is written as a `c` line before the header.
+    pub fn to_file_with_comments<P>(&self, path: P, comments: &[&str]) -> std::io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut writer = std::io::BufWriter::new(fs::File::create(path)?);
+        for line in comments.iter().flat_map(|comment| comment.split('\n')) {
+            writeln!(writer, "{}", format!("c {line}").trim_end())?;
+        }
+        self.to_writer(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Save the instance to a file in Conjunctive Normal Form, atomically:
+    /// the instance is written to a temporary file in the same directory, then renamed to `path`,
+    /// so `path` is never left partially written. The temporary file is removed on failure.
+    /// Concurrent writes to the same path each use their own temporary file, the last rename winning.
+    pub fn to_file_atomic<P>(&self, path: P) -> std::io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .ok_or(std::io::ErrorKind::InvalidInput)?
+            .to_string_lossy();
+        let temp_path = path.with_file_name(format!(
+            ".{file_name}.{}.{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let result = fs::File::create(&temp_path).and_then(|file| {
+            let mut writer = std::io::BufWriter::new(file);
+            self.to_writer(&mut writer)?;
+            writer.into_inner()?.sync_all()?;
+            fs::rename(&temp_path, path)
+        });
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+
+        result
+    }
+
+    /// Writes the instance in Conjunctive Normal Form to the given writer,
+    /// with the quantifier prefix in QDIMACS if there is one.
+    pub fn to_writer<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        self.to_writer_with(w, &WriteOptions::default())
+    }
 
-        writeln!(file, "p cnf {} {}", self.vars.len(), self.clauses.len())?;
+    /// Writes the instance in Conjunctive Normal Form to the given writer, formatted as described by `options`,
+    /// with the quantifier prefix in QDIMACS if there is one.
+    pub fn to_writer_with<W: Write>(
+        &self,
+        w: &mut W,
+        options: &WriteOptions,
+    ) -> std::io::Result<()> {
+        writeln!(w, "p cnf {} {}", self.vars.len(), self.clauses.len())?;
+        for (quantifier, vars) in &self.quantifiers {
+            write!(w, "{} ", quantifier.as_char())?;
+            for &var in vars {
+                write!(w, "{} ", Literal::new(var, false).as_cnf())?;
+            }
+            writeln!(w, "0")?;
+        }
         for clause in &self.clauses {
-            for elem in clause.get_literals() {
-                write!(file, "{} ", elem.as_cnf())?;
+            write_clause(w, clause, options)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the instance in incremental CNF (iCNF) to the given writer:
+    /// the `p inccnf` header, the clauses, then one `a ... 0` line per assumption set.
+    pub fn to_icnf<W: Write>(
+        &self,
+        assumption_sets: &[Vec<Literal>],
+        w: &mut W,
+    ) -> std::io::Result<()> {
+        writeln!(w, "p inccnf")?;
+        self.write_clauses(w)?;
+
+        for assumptions in assumption_sets {
+            write!(w, "a ")?;
+            for lit in assumptions {
+                write!(w, "{} ", lit.as_cnf())?;
             }
-            writeln!(file, "0")?;
+            writeln!(w, "0")?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the clauses as lists of CNF integers (variable `i` is `i + 1`, negated if negative),
+    /// without the terminating 0, as taken by `pysat.formula.CNF(from_clauses=...)` once serialized to JSON.
+    /// The number of variables is not included, pysat deduces it from the largest literal.
+    pub fn to_python_literals(&self) -> Vec<Vec<isize>> {
+        self.clauses
+            .iter()
+            .map(|clause| clause.get_literals().iter().map(Literal::as_cnf).collect())
+            .collect()
+    }
+
+    /// Writes the clauses, one per line and terminated by 0.
+    fn write_clauses<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        for clause in &self.clauses {
+            writeln!(w, "{clause:#}")?;
         }
 
         Ok(())
@@ -152,6 +646,13 @@ impl Instance {
         self.sample_new_variables_with(&mut rng)
     }
 
+    /// Randomly sample new variables with an RNG seeded with `seed`, for reproducible runs
+    #[cfg(feature = "rand")]
+    pub fn sample_new_variables_seeded(&mut self, seed: u64) -> &BoolVec {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        self.sample_new_variables_with(&mut rng)
+    }
+
     /// Randomly sample new variables with a given RNG
     #[cfg(feature = "rand")]
     pub fn sample_new_variables_with<T: Rng>(&mut self, rng: &mut T) -> &BoolVec {
@@ -160,10 +661,25 @@ impl Instance {
                 .take(self.vars.len())
                 .collect::<Vec<_>>(),
         );
+        self.touch();
 
         &self.vars
     }
 
+    /// Randomly permutes the clauses and the literals of each clause, leaving the formula unchanged,
+    /// to check that a solver does not depend on the input order. The clause labels and kinds are dropped.
+    #[cfg(feature = "rand")]
+    pub fn shuffle<R: Rng>(&mut self, rng: &mut R) {
+        use rand::seq::SliceRandom;
+
+        self.clauses.shuffle(rng);
+        for clause in &mut self.clauses {
+            clause.shuffle(rng);
+        }
+        self.clear_clause_metadata();
+        self.touch();
+    }
+
     /// Returns the number of satisfied clauses
     pub fn count_sat(&self) -> usize {
         self.clauses
@@ -172,24 +688,2950 @@ impl Instance {
             .count()
     }
 
+    /// Returns the number of clauses satisfied by variable values packed in 64-bit words, the value of the variable `i`
+    /// being the bit `i % 64` of the word `i / 64` (see `Literal::eval_bits`), without converting them to a `BoolVec`.
+    /// This is the layout of `bitvec::BitVec<u64, Lsb0>`.
+    /// Panics if a literal is not present in the given words.
+    pub fn count_sat_bits(&self, bits: &[u64]) -> usize {
+        self.clauses
+            .iter()
+            .filter(|clause| clause.test_sat_bits(bits))
+            .count()
+    }
+
+    /// Returns, for each clause, whether it is satisfied by the given variable values
+    pub fn satisfaction_mask(&self, vars: &BoolVec) -> BoolVec {
+        let mut mask = BoolVec::with_capacity(self.clauses.len());
+        for clause in &self.clauses {
+            mask.push(clause.test_sat(vars));
+        }
+
+        mask
+    }
+
+    /// Returns the number of clauses `(unsat, critically_sat, robustly_sat)` under the given variable values,
+    /// where a clause is critically satisfied by exactly one true literal and robustly satisfied by more.
+    /// Flipping the variable of the sole true literal breaks a critically satisfied clause.
+    /// Repeated literals are counted with repetition.
+    pub fn satisfaction_breakdown(&self, vars: &BoolVec) -> (usize, usize, usize) {
+        let mut breakdown = (0, 0, 0);
+        for clause in &self.clauses {
+            match clause.satisfied_count(vars) {
+                0 => breakdown.0 += 1,
+                1 => breakdown.1 += 1,
+                _ => breakdown.2 += 1,
+            }
+        }
+
+        breakdown
+    }
+
+    /// Returns `(make_count, break_count)`, the number of clauses that flipping `var` in the given variable values
+    /// would make satisfied and would make unsatisfied, as used by WalkSAT-like local search.
+    /// A clause breaks only if the literals of `var` were its sole true literals.
+    /// Builds an occurrence index, use `flip_scores_with` to reuse one across flips.
+    pub fn flip_scores(&self, vars: &BoolVec, var: usize) -> (usize, usize) {
+        self.flip_scores_with(&self.occurrence_index(), vars, var)
+    }
+
+    /// Returns the make and break counts of flipping `var`, see `flip_scores`,
+    /// looking only at the clauses containing `var` in the given occurrence index.
+    /// The index must have been built from the current clauses, the variable values do not matter.
+    pub fn flip_scores_with(
+        &self,
+        index: &OccurrenceIndex,
+        vars: &BoolVec,
+        var: usize,
+    ) -> (usize, usize) {
+        let mut scores = (0, 0);
+        for i in index.var_occurrences(var) {
+            let clause = &self.clauses[i];
+            let before = clause.test_sat(vars);
+            let after = clause
+                .get_literals()
+                .iter()
+                .any(|lit| lit.eval_with(vars) != (lit.index() == var));
+
+            match (before, after) {
+                (false, true) => scores.0 += 1,
+                (true, false) => scores.1 += 1,
+                _ => {}
+            }
+        }
+
+        scores
+    }
+
+    /// Returns how many more clauses the assignment `b` satisfies than the assignment `a`, negative if fewer.
+    /// Builds an occurrence index, use `sat_count_delta_with` to reuse one across calls.
+    /// Panics if the assignments do not have the same length.
+    pub fn sat_count_delta(&self, a: &BoolVec, b: &BoolVec) -> isize {
+        self.sat_count_delta_with(&self.occurrence_index(), a, b)
+    }
+
+    /// Returns the difference of the number of clauses satisfied by `b` and by `a`, see `sat_count_delta`,
+    /// looking only at the clauses containing a variable on which the assignments differ in the given occurrence index.
+    /// For assignments differing on a few variables, this is much cheaper than counting the satisfied clauses twice,
+    /// and for a single variable it is the make count minus the break count of `flip_scores`.
+    /// The index must have been built from the current clauses.
+    /// Panics if the assignments do not have the same length.
+    pub fn sat_count_delta_with(&self, index: &OccurrenceIndex, a: &BoolVec, b: &BoolVec) -> isize {
+        assert_eq!(a.len(), b.len(), "assignments of different lengths");
+
+        let mut touched: Vec<_> = (0..a.len())
+            .filter(|&var| a.get(var) != b.get(var))
+            .flat_map(|var| index.var_occurrences(var))
+            .collect();
+        touched.sort_unstable();
+        touched.dedup();
+
+        touched
+            .into_iter()
+            .map(|i| {
+                let clause = &self.clauses[i];
+                clause.test_sat(b) as isize - clause.test_sat(a) as isize
+            })
+            .sum()
+    }
+
+    /// Returns, for each clause, how many times it became unsatisfied over `samples` random single-variable flips
+    /// of the current variables, a proxy of how constraining the clause is
+    #[cfg(feature = "rand")]
+    pub fn flip_sensitivity<R: Rng>(&self, samples: usize, rng: &mut R) -> Vec<usize> {
+        let mut counts = vec![0; self.clauses.len()];
+        if self.num_vars() == 0 {
+            return counts;
+        }
+
+        let before = self.satisfaction_mask(&self.vars);
+        let mut flipped = BoolVec::from(self.vars.into_vec());
+
+        for _ in 0..samples {
+            let var = rng.gen_range(0..self.num_vars());
+            flipped.negate(var);
+
+            let after = self.satisfaction_mask(&flipped);
+            for (count, (b, a)) in counts.iter_mut().zip(before.into_iter().zip(&after)) {
+                if b && !a {
+                    *count += 1;
+                }
+            }
+
+            flipped.negate(var);
+        }
+
+        counts
+    }
+
+    /// Returns the indices of the clauses not satisfied by the given variable values, in increasing order
+    pub fn unsat_clauses(&self, vars: &BoolVec) -> Vec<usize> {
+        self.enumerate_clauses()
+            .filter(|(_, clause)| !clause.test_sat(vars))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the index of the shortest clause unsatisfied by the assignment `vars`, along with the clause,
+    /// or `None` if all clauses are satisfied. Among clauses of the same length, the first one is returned.
+    pub fn shortest_unsat_clause(&self, vars: &BoolVec) -> Option<(usize, &Clause)> {
+        self.unsat_clauses(vars)
+            .into_iter()
+            .map(|i| (i, &self.clauses[i]))
+            .min_by_key(|(_, clause)| clause.len())
+    }
+
+    /// Searches for a model with the Novelty+ local search, starting from the current variables,
+    /// for at most `max_flips` flips. Returns whether a model was found, the last assignment being kept in `vars`.
+    ///
+    /// Each flip picks a random unsatisfied clause. With probability `wp`, a random variable of the clause is flipped
+    /// (random walk). Otherwise its variables are ranked by make minus break count (see `flip_scores`),
+    /// ties going to the least recently flipped: the best one is flipped, unless it is the most recently flipped variable
+    /// of the clause, in which case the second best one is flipped with probability `p`.
+    /// A clause none of whose variables was flipped yet has no most recently flipped variable.
+    /// This is incomplete: returning false does not mean the instance is unsatisfiable.
+    /// Panics if `p` or `wp` is not in `[0, 1]`.
+    #[cfg(feature = "rand")]
+    pub fn novelty<R: Rng>(&mut self, max_flips: usize, p: f64, wp: f64, rng: &mut R) -> bool {
+        assert!(
+            (0.0..=1.0).contains(&p),
+            "novelty probability {p} not in [0, 1]"
+        );
+        assert!(
+            (0.0..=1.0).contains(&wp),
+            "random walk probability {wp} not in [0, 1]"
+        );
+
+        if self.clauses.iter().any(Clause::is_empty) {
+            return false;
+        }
+
+        let index = self.occurrence_index();
+        let mut vars = BoolVec::from(self.vars.into_vec());
+        // Flip step of each variable, 0 if never flipped
+        let mut last_flip = vec![0; self.num_vars()];
+
+        let mut found = false;
+        for step in 1..=max_flips {
+            let unsat = self.unsat_clauses(&vars);
+            if unsat.is_empty() {
+                found = true;
+                break;
+            }
+
+            let clause = &self.clauses[unsat[rng.gen_range(0..unsat.len())]];
+            let mut candidates: Vec<_> = clause.get_literals().iter().map(Literal::index).collect();
+            candidates.sort_unstable();
+            candidates.dedup();
+
+            let var = if rng.gen_bool(wp) {
+                candidates[rng.gen_range(0..candidates.len())]
+            } else {
+                let mut ranked: Vec<_> = candidates
+                    .iter()
+                    .map(|&var| {
+                        let (make, breaks) = self.flip_scores_with(&index, &vars, var);
+                        (make as isize - breaks as isize, var)
+                    })
+                    .collect();
+                ranked.sort_by_key(|&(score, var)| (std::cmp::Reverse(score), last_flip[var]));
+
+                let youngest = *candidates
+                    .iter()
+                    .max_by_key(|&&var| last_flip[var])
+                    .unwrap();
+                let novel = last_flip[youngest] > 0;
+                match ranked[..] {
+                    [(_, best), (_, second), ..]
+                        if novel && best == youngest && rng.gen_bool(p) =>
+                    {
+                        second
+                    }
+                    [(_, best), ..] => best,
+                    [] => unreachable!("empty clauses are excluded"),
+                }
+            };
+
+            vars.negate(var);
+            last_flip[var] = step;
+        }
+
+        found = found || self.unsat_clauses(&vars).is_empty();
+        self.set_model(vars);
+        found
+    }
+
+    /// Searches for an assignment satisfying as many clauses as possible (MaxSAT) with a WalkSAT-like local search,
+    /// starting from the current variables. Each iteration flips a variable of a random unsatisfied clause,
+    /// either at random or the one with the best make and break counts (see `flip_scores`).
+    /// The best assignment found is kept in `vars` and returned with its number of satisfied clauses.
+    /// This is a heuristic: the assignment is not proven optimal, and the search stops early only if all clauses are satisfied.
+    #[cfg(feature = "rand")]
+    pub fn maximize<R: Rng>(&mut self, iterations: usize, rng: &mut R) -> (BoolVec, usize) {
+        let index = self.occurrence_index();
+        let mut vars = BoolVec::from(self.vars.into_vec());
+        let mut score = self.count_sat();
+        let mut best = (BoolVec::from(vars.into_vec()), score);
+
+        for _ in 0..iterations {
+            let unsat: Vec<_> = self
+                .clauses
+                .iter()
+                .filter(|clause| !clause.is_empty() && !clause.test_sat(&vars))
+                .collect();
+            if unsat.is_empty() {
+                break;
+            }
+
+            let literals = unsat[rng.gen_range(0..unsat.len())].get_literals();
+            let var = if rng.gen_bool(0.5) {
+                literals[rng.gen_range(0..literals.len())].index()
+            } else {
+                literals
+                    .iter()
+                    .map(|lit| lit.index())
+                    .max_by_key(|&var| {
+                        let (make, breaks) = self.flip_scores_with(&index, &vars, var);
+                        make as isize - breaks as isize
+                    })
+                    .unwrap()
+            };
+
+            let (make, breaks) = self.flip_scores_with(&index, &vars, var);
+            score = score + make - breaks;
+            vars.negate(var);
+
+            if score > best.1 {
+                best = (BoolVec::from(vars.into_vec()), score);
+            }
+        }
+
+        self.set_model(BoolVec::from(best.0.into_vec()));
+        best
+    }
+
     /// Returns true if all clauses are satisfied
+    /// Stops at the first unsatisfied clause, unlike `count_sat`.
     pub fn is_sat(&self) -> bool {
-        self.count_sat() == self.clauses.len()
+        self.clauses
+            .iter()
+            .all(|clause| clause.test_sat(&self.vars))
     }
 
-    /// Returns the ratio of clauses to variables
-    pub fn clause_to_vars(&self) -> f32 {
-        self.clauses.len() as f32 / self.vars.len() as f32
+    /// Evaluates the instance as a formula in Disjunctive Normal Form under the given variable values:
+    /// each clause is reinterpreted as a conjunctive term, and the result is true if any term has all its literals true.
+    /// This is an alternate reading of the same clauses, not a CNF to DNF transformation.
+    pub fn eval_as_dnf(&self, vars: &BoolVec) -> bool {
+        self.clauses
+            .iter()
+            .any(|term| term.iter_eval(vars).all(|x| x))
     }
 
-    /// Returns a reference to the clauses
-    pub fn get_clauses(&self) -> &Vec<Clause> {
-        &self.clauses
+    /// Returns the number of variables
+    pub fn num_vars(&self) -> usize {
+        self.vars.len()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn test() {}
+    /// Returns, for each variable, whether it appears in at least one clause.
+    /// After preprocessing, this tells the variables the formula still depends on, unlike `num_vars`,
+    /// and counting the set bits gives the effective number of variables.
+    /// Panics if a literal is not present in the variables.
+    pub fn used_vars(&self) -> BoolVec {
+        let mut used = boolvec![false; self.num_vars()];
+        for lit in self.clauses.iter().flat_map(Clause::get_literals) {
+            used.set(lit.index(), true).expect("literal not present");
+        }
+
+        used
+    }
+
+    /// Compiles the instance to a form evaluated with bitwise operations.
+    /// Only available for instances with at most 64 variables (`DenseInstance::MAX_VARS`), returns `None` otherwise.
+    pub fn compile_dense(&self) -> Option<DenseInstance> {
+        DenseInstance::compile(self)
+    }
+
+    /// Copies the clauses to a contiguous form, faster to evaluate on large instances.
+    pub fn compact(&self) -> CompactClauses {
+        CompactClauses::new(self)
+    }
+
+    /// Returns the width of the instance, the maximal width of its clauses (0 without clauses).
+    /// In proof complexity, the width of a resolution refutation is the maximal width of its clauses,
+    /// which is at least the width of the instance.
+    pub fn max_width(&self) -> usize {
+        self.clauses.iter().map(Clause::width).max().unwrap_or(0)
+    }
+
+    /// Returns whether all clauses are Horn clauses, with at most one positive literal.
+    /// Horn instances are decidable in polynomial time by unit propagation.
+    pub fn is_horn(&self) -> bool {
+        self.clauses.iter().all(Clause::is_horn)
+    }
+
+    /// Returns the total number of literals of the clauses, repeated literals included
+    pub fn num_literals(&self) -> usize {
+        self.clauses.iter().map(Clause::len).sum()
+    }
+
+    /// Returns the average number of literals per clause (NaN without clauses)
+    pub fn avg_clause_len(&self) -> f64 {
+        self.num_literals() as f64 / self.clauses.len() as f64
+    }
+
+    /// Returns the ratio of clauses to variables
+    pub fn clause_to_vars(&self) -> f32 {
+        self.clauses.len() as f32 / self.vars.len() as f32
+    }
+
+    /// Returns a one-line summary of the instance and its current variables, suitable for logging,
+    /// in the stable format `vars=<n> clauses=<m> sat=<count_sat> ratio=<clause_to_vars, 3 decimals>`.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "vars={} clauses={} sat={} ratio={:.3}",
+            self.num_vars(),
+            self.clauses.len(),
+            self.count_sat(),
+            self.clause_to_vars()
+        )
+    }
+
+    /// Returns the generation of the instance, an identifier of its state changed by every mutation
+    /// through its methods (`add_clause`, `remove_clause`, `assign`, ...).
+    /// Derived structures such as `OccurrenceIndex` record the generation they were built at to detect staleness.
+    /// Writing to `vars` directly does not change the generation.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Marks the instance as mutated, invalidating the derived structures.
+    fn touch(&mut self) {
+        self.generation = next_generation();
+    }
+
+    /// Drops the labels and kinds of the clauses, when the clauses are rearranged beyond tracking.
+    fn clear_clause_metadata(&mut self) {
+        self.labels.clear();
+        self.kinds.clear();
+    }
+
+    /// Appends a clause to the instance.
+    pub fn add_clause(&mut self, clause: Clause) {
+        self.clauses.push(clause);
+        if !self.labels.is_empty() {
+            self.labels.push(None);
+        }
+        if !self.kinds.is_empty() {
+            self.kinds.push(ClauseKind::Original);
+        }
+        self.touch();
+    }
+
+    /// Appends a clause learned by a solver, which `truncate_clauses` may remove, see `ClauseKind`.
+    pub fn add_learned_clause(&mut self, clause: Clause) {
+        self.add_clause(clause);
+        self.kinds.resize(self.clauses.len(), ClauseKind::Original);
+        *self.kinds.last_mut().unwrap() = ClauseKind::Learned;
+    }
+
+    /// Reserves capacity for at least `additional` more clauses, to add many clauses without reallocating.
+    pub fn reserve(&mut self, additional: usize) {
+        self.clauses.reserve(additional);
+    }
+
+    /// Appends a clause to the instance unless a clause with the same literals, in any order and repetition,
+    /// is already present (see `Clause::canonical`). Returns whether the clause was added.
+    /// The canonical forms of the clauses are kept between calls, and collected again after other mutations.
+    pub fn add_clause_dedup(&mut self, clause: Clause) -> bool {
+        let canonical = match &mut self.canonical_clauses {
+            Some((generation, canonical)) if *generation == self.generation => canonical,
+            cache => {
+                let canonical = self.clauses.iter().map(Clause::canonical).collect();
+                &mut cache.insert((self.generation, canonical)).1
+            }
+        };
+
+        if !canonical.insert(clause.canonical()) {
+            return false;
+        }
+
+        self.add_clause(clause);
+        if let Some((generation, _)) = &mut self.canonical_clauses {
+            *generation = self.generation;
+        }
+        true
+    }
+
+    /// Removes and returns the clause at `index`, shifting the following ones.
+    /// Panics if `index` is out of bounds.
+    pub fn remove_clause(&mut self, index: usize) -> Clause {
+        let clause = self.clauses.remove(index);
+        if !self.labels.is_empty() {
+            self.labels.remove(index);
+        }
+        if !self.kinds.is_empty() {
+            self.kinds.remove(index);
+        }
+        self.touch();
+        clause
+    }
+
+    /// Sorts the clauses by decreasing `score`, computed once per clause and compared with `f64::total_cmp`,
+    /// keeping the order of the clauses of equal score. The clause labels and kinds follow their clauses.
+    /// Along with `truncate_clauses`, this is the clause deletion of CDCL solvers.
+    pub fn sort_clauses_by_score(&mut self, score: impl Fn(&Clause) -> f64) {
+        let mut scored: Vec<_> = self.clauses.iter().map(score).enumerate().collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let order: Vec<_> = scored.into_iter().map(|(i, _)| i).collect();
+        self.select_clauses(&order);
+    }
+
+    /// Removes the learned clauses past the first `keep` clauses, the lowest-scoring ones after `sort_clauses_by_score`,
+    /// and returns the number of clauses removed.
+    /// The original clauses are never removed, wherever they are, so that the formula is unchanged:
+    /// more than `keep` clauses remain if original clauses lie past the first `keep`.
+    pub fn truncate_clauses(&mut self, keep: usize) -> usize {
+        let kept: Vec<_> = (0..self.clauses.len())
+            .filter(|&i| i < keep || self.clause_kind(i) == Some(ClauseKind::Original))
+            .collect();
+
+        let removed = self.clauses.len() - kept.len();
+        if removed > 0 {
+            self.select_clauses(&kept);
+        }
+        removed
+    }
+
+    /// Replaces the clauses, along with their labels and kinds, by the clauses at the indices `selected`, in that order.
+    /// Each index must appear at most once.
+    fn select_clauses(&mut self, selected: &[usize]) {
+        let mut clauses: Vec<_> = std::mem::take(&mut self.clauses)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.clauses = selected
+            .iter()
+            .map(|&i| clauses[i].take().expect("clause selected twice"))
+            .collect();
+
+        if !self.labels.is_empty() {
+            let mut labels = std::mem::take(&mut self.labels);
+            self.labels = selected.iter().map(|&i| labels[i].take()).collect();
+        }
+        if !self.kinds.is_empty() {
+            self.kinds = selected.iter().map(|&i| self.kinds[i]).collect();
+        }
+        self.touch();
+    }
+
+    /// Sets the value of the variable at `index`.
+    /// Returns `None` if the variable is not present.
+    pub fn assign(&mut self, index: usize, value: bool) -> Option<()> {
+        self.vars.set(index, value)?;
+        self.touch();
+        Some(())
+    }
+
+    /// Returns the current assignment of the variables
+    pub fn model(&self) -> &BoolVec {
+        &self.vars
+    }
+
+    /// Replaces the current assignment of the variables.
+    /// Panics if the length of `model` is not `num_vars()`.
+    pub fn set_model(&mut self, model: BoolVec) {
+        assert_eq!(
+            model.len(),
+            self.num_vars(),
+            "model length does not match the number of variables"
+        );
+        self.vars = model;
+        self.touch();
+    }
+
+    /// Returns an index of the clauses containing each literal
+    pub fn occurrence_index(&self) -> OccurrenceIndex {
+        OccurrenceIndex::new(self)
+    }
+
+    /// Returns the indices of the clauses that setting `lit` true would satisfy, the ones containing `lit`,
+    /// and of the clauses it would shorten, the ones containing its negation, which become falsified
+    /// if that negation is their last unassigned literal. Both lists are in increasing order,
+    /// a clause containing both literals appearing in both.
+    pub fn literal_impact(&self, lit: Literal) -> (Vec<usize>, Vec<usize>) {
+        let index = self.occurrence_index();
+
+        (
+            index.occurrences(lit).to_vec(),
+            index.occurrences(lit.negated()).to_vec(),
+        )
+    }
+
+    /// Returns, for each variable, the polarities in which it appears in the clauses.
+    /// Variables appearing in a single polarity are pure literals, and absent variables often point to encoding bugs.
+    pub fn polarity_profile(&self) -> Vec<VarPolarity> {
+        let index = self.occurrence_index();
+        (0..self.num_vars())
+            .map(|var| index.polarity(var))
+            .collect()
+    }
+
+    /// Returns the QBF quantifier prefix, empty for plain SAT
+    pub fn quantifiers(&self) -> &[(Quantifier, Vec<usize>)] {
+        &self.quantifiers
+    }
+
+    /// Sets the QBF quantifier prefix, written in QDIMACS by `to_file`
+    pub fn set_quantifiers(&mut self, quantifiers: Vec<(Quantifier, Vec<usize>)>) {
+        self.quantifiers = quantifiers;
+        self.touch();
+    }
+
+    /// Returns whether the clause at `index` is original or learned (added by `add_learned_clause`),
+    /// or `None` if `index` is out of bounds.
+    /// Kinds follow the clauses like the labels (see `clause_label`), the clauses becoming original when they are dropped.
+    pub fn clause_kind(&self, index: usize) -> Option<ClauseKind> {
+        if index >= self.clauses.len() {
+            return None;
+        }
+
+        Some(self.kinds.get(index).copied().unwrap_or_default())
+    }
+
+    /// Returns the label of the clause at `index`, read from its trailing annotation `c: label`
+    /// when parsed with `ParseOptions::clause_labels`, to map the clauses (of an UNSAT core for instance)
+    /// back to their source. Returns `None` if the clause has no label or `index` is out of bounds.
+    /// Labels follow the clauses through `add_clause`, `remove_clause`, `sort_clauses_by_score` and `truncate_clauses`,
+    /// but are dropped by the other mutations removing or reordering many clauses at once.
+    pub fn clause_label(&self, index: usize) -> Option<&str> {
+        self.labels.get(index)?.as_deref()
+    }
+
+    /// Returns a reference to the clauses
+    /// Prefer `clauses`, which does not expose the backing container.
+    pub fn get_clauses(&self) -> &Vec<Clause> {
+        &self.clauses
+    }
+
+    /// Returns an iterator over the clauses
+    pub fn clauses(&self) -> impl Iterator<Item = &Clause> {
+        self.clauses.iter()
+    }
+
+    /// Returns an iterator over the clauses along with their indices
+    pub fn enumerate_clauses(&self) -> impl Iterator<Item = (usize, &Clause)> {
+        self.clauses.iter().enumerate()
+    }
+
+    /// Returns an iterator over mutable references to the clauses, for in-place transformations.
+    /// The generation is changed upfront, whether or not the clauses are actually modified.
+    pub fn clauses_mut(&mut self) -> impl Iterator<Item = &mut Clause> {
+        self.touch();
+        self.clauses.iter_mut()
+    }
+
+    /// Consumes the instance and returns its clauses
+    pub fn into_clauses(self) -> Vec<Clause> {
+        self.clauses
+    }
+
+    /// Returns the number of bytes allocated on the heap for the variables and the clauses.
+    pub fn heap_size(&self) -> usize {
+        self.vars.bytes_capacity()
+            + self.clauses.capacity() * std::mem::size_of::<Clause>()
+            + self.clauses.iter().map(Clause::heap_size).sum::<usize>()
+    }
+
+    /// Shrinks the capacity of the clauses and of their literals as much as possible,
+    /// reclaiming memory after clauses or literals have been removed.
+    pub fn shrink_to_fit(&mut self) {
+        self.clauses.shrink_to_fit();
+        for clause in &mut self.clauses {
+            clause.shrink_to_fit();
+        }
+    }
+
+    /// Returns a new instance made of the clauses at the given indices, in the given order.
+    /// The variables are kept as is, so models of the sub-instance are comparable to models of this one.
+    /// Panics if an index is out of bounds.
+    pub fn subformula(&self, indices: &[usize]) -> Instance {
+        let clauses = indices
+            .iter()
+            .map(|&i| {
+                assert!(
+                    i < self.clauses.len(),
+                    "clause index {i} out of bounds for {} clauses",
+                    self.clauses.len()
+                );
+                self.clauses[i].clone()
+            })
+            .collect();
+
+        let mut sub = Self::new(BoolVec::from(self.vars.into_vec()), clauses);
+        sub.set_quantifiers(self.quantifiers.clone());
+        sub
+    }
+
+    /// Returns the residual instance under the partial assignment `a`: the clauses it satisfies are removed,
+    /// and the literals it falsifies are removed from the other clauses, possibly leaving empty clauses.
+    /// The variables are kept as is, the assigned ones no longer appearing in the clauses.
+    pub fn simplify_under(&self, a: &Assignment) -> Instance {
+        let clauses = self
+            .clauses
+            .iter()
+            .filter(|clause| clause.evaluate(a) != ClauseValue::True)
+            .map(|clause| {
+                clause
+                    .get_literals()
+                    .iter()
+                    .filter(|&&lit| a.eval(lit).is_none())
+                    .copied()
+                    .collect()
+            })
+            .collect();
+
+        Self::new(BoolVec::from(self.vars.into_vec()), clauses)
+    }
+
+    /// Returns the literals of the unit clauses, in clause order.
+    pub fn unit_literals(&self) -> Vec<Literal> {
+        self.clauses
+            .iter()
+            .filter_map(Clause::unit_literal)
+            .collect()
+    }
+
+    /// Sets the variables so that every unit clause is satisfied.
+    /// Returns the number of variables set, or the first literal contradicting a previous unit clause.
+    /// Panics if a unit literal is not present in the variables.
+    pub fn propagate_units_into_vars(&mut self) -> Result<usize, Literal> {
+        let mut assigned = HashSet::new();
+
+        for lit in self.unit_literals() {
+            if assigned.contains(&lit.negated()) {
+                return Err(lit);
+            }
+
+            if assigned.insert(lit) {
+                self.vars.set(lit.index(), !lit.is_negated()).unwrap();
+            }
+        }
+        self.touch();
+
+        Ok(assigned.len())
+    }
+
+    /// Applies the unit clauses until fixpoint: the clauses satisfied by a unit literal are removed,
+    /// unit clauses included, and the falsified literals are struck out of the others, possibly producing new unit clauses.
+    /// The propagated values are set in the variables, so that a model of the simplified instance is a model of the original one.
+    /// Returns the number of clauses removed, or an error if an empty clause is derived (or already present),
+    /// proving the instance unsatisfiable, in which case the instance is left partially simplified.
+    /// The clause labels and kinds are dropped if a clause is removed.
+    /// Panics if a unit literal is not present in the variables.
+    #[allow(clippy::result_unit_err)] // The unsatisfiability is the only error
+    pub fn propagate_and_simplify(&mut self) -> Result<usize, ()> {
+        let num_clauses = self.clauses.len();
+        let mut a = Assignment::new(self.num_vars());
+        self.touch();
+
+        loop {
+            if self.clauses.iter().any(Clause::is_empty) {
+                return Err(());
+            }
+
+            let units = self.unit_literals();
+            if units.is_empty() {
+                break;
+            }
+            for lit in units {
+                if a.eval(lit) == Some(false) {
+                    return Err(());
+                }
+                a.assign_literal(lit).expect("unit literal not present");
+                self.vars.set(lit.index(), !lit.is_negated());
+            }
+
+            self.clear_clause_metadata();
+            self.clauses
+                .retain(|clause| clause.evaluate(&a) != ClauseValue::True);
+            for clause in &mut self.clauses {
+                clause.retain(|&lit| a.eval(lit).is_none());
+            }
+        }
+
+        Ok(num_clauses - self.clauses.len())
+    }
+
+    /// Returns the distinct non-tautological resolvents derivable in one resolution step
+    /// from pairs of clauses, excluding the ones already in the instance.
+    /// Deriving the empty clause proves the instance unsatisfiable.
+    pub fn resolution_step(&self) -> Vec<Clause> {
+        let mut seen: HashSet<Clause> = self.clauses.iter().map(Clause::canonical).collect();
+
+        let mut resolvents = vec![];
+        for (i, a) in self.clauses.iter().enumerate() {
+            for b in &self.clauses[i + 1..] {
+                for lit in a.get_literals() {
+                    let Some(resolvent) = a.resolve(b, lit.index()) else {
+                        continue;
+                    };
+
+                    if !resolvent.is_tautology() && seen.insert(resolvent.clone()) {
+                        resolvents.push(resolvent);
+                    }
+                }
+            }
+        }
+
+        resolvents
+    }
+
+    /// Returns the number of non-tautological resolvents on `var` between the clauses containing `var` positively
+    /// and the ones containing it negated, without building them.
+    /// This bounds the growth of eliminating `var` by resolution, to compare with the number of clauses it removes.
+    pub fn resolvent_count(&self, var: usize) -> usize {
+        let (positive, negative): (Vec<_>, Vec<_>) = self
+            .clauses
+            .iter()
+            .filter(|clause| clause.contains_var(var))
+            .partition(|clause| clause.get_literals().contains(&Literal::new(var, false)));
+
+        positive
+            .iter()
+            .map(|a| {
+                negative
+                    .iter()
+                    .filter(|b| !a.resolvent_is_tautology(b, var))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Repeatedly adds the resolvents of `resolution_step` to the instance until the empty clause is derived,
+    /// no new resolvent can be derived, or `max_new` clauses have been added.
+    /// Returns true if the empty clause was derived, proving the instance unsatisfiable.
+    pub fn saturate_resolution(&mut self, max_new: usize) -> bool {
+        let mut added = 0;
+
+        while added < max_new {
+            let resolvents = self.resolution_step();
+            if resolvents.is_empty() {
+                break;
+            }
+
+            for resolvent in resolvents.into_iter().take(max_new - added) {
+                let is_empty = resolvent.is_empty();
+                self.add_clause(resolvent);
+                added += 1;
+
+                if is_empty {
+                    return true;
+                }
+            }
+        }
+
+        self.clauses.iter().any(Clause::is_empty)
+    }
+
+    /// Returns the prime implicants of the formula, each as a `Clause` read as a conjunction of literals:
+    /// the minimal consistent terms entailing the formula, that is hitting every non-tautological clause.
+    /// The terms have their literals in increasing order, and are sorted in lexicographic order of their literals.
+    /// The formula without clauses has the single empty term, and an unsatisfiable one may have none.
+    /// The number of prime implicants can be exponential in the number of clauses,
+    /// see `prime_implicants_up_to` to bound their length.
+    pub fn prime_implicants(&self) -> Vec<Clause> {
+        self.prime_implicants_up_to(usize::MAX)
+    }
+
+    /// Returns the prime implicants of at most `max_len` literals, see `prime_implicants`.
+    /// They are computed by branching on the literals of the first clause not yet hit, to a depth of `max_len`,
+    /// then removing the terms absorbed by shorter ones.
+    pub fn prime_implicants_up_to(&self, max_len: usize) -> Vec<Clause> {
+        let clauses: Vec<_> = self
+            .clauses
+            .iter()
+            .filter(|clause| !clause.is_tautology())
+            .collect();
+        let mut implicants = HashSet::new();
+        collect_implicants(&clauses, max_len, &mut vec![], &mut implicants);
+
+        let mut implicants: Vec<_> = implicants.into_iter().collect();
+        implicants.sort_by_key(Clause::len);
+
+        let mut primes: Vec<Clause> = vec![];
+        for implicant in implicants {
+            let absorbed = primes.iter().any(|prime| {
+                prime
+                    .get_literals()
+                    .iter()
+                    .all(|&lit| implicant.is_satisfied_by(lit))
+            });
+            if !absorbed {
+                primes.push(implicant);
+            }
+        }
+
+        primes.sort_by(|a, b| a.get_literals().cmp(b.get_literals()));
+        primes
+    }
+
+    /// Returns, for each pair of variables `(i, j)` with `i < j` sharing at least one clause,
+    /// the number of clauses containing both. Pairs sharing no clause are absent from the map.
+    /// This is the weighted primal graph of the instance, built in O(sum of squared clause lengths).
+    pub fn cooccurrence(&self) -> HashMap<(usize, usize), usize> {
+        let mut counts = HashMap::new();
+
+        for clause in &self.clauses {
+            let vars: Vec<_> = clause.var_set().into_iter().collect();
+            for (k, &i) in vars.iter().enumerate() {
+                for &j in &vars[k + 1..] {
+                    *counts.entry((i, j)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        counts
+    }
+
+    /// Returns the degree of each variable in the primal graph (see `cooccurrence`), indexed by variable:
+    /// the number of distinct other variables it shares a clause with. Sort it for a degree distribution
+    /// comparable across instances.
+    pub fn degree_sequence(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.num_vars()];
+        for (i, j) in self.cooccurrence().into_keys() {
+            degrees[i] += 1;
+            degrees[j] += 1;
+        }
+
+        degrees
+    }
+
+    /// Returns groups of variables among which at most one can be true, recognized as the cliques of the graph
+    /// linking the variables of each negative binary clause `(¬xi ∨ ¬xj)`, as produced by pairwise at-most-one encodings.
+    /// The cliques are found greedily: each binary clause not yet covered by a group seeds a clique, extended
+    /// with the variables linked to all its members in increasing index order. Each group is thus maximal and sorted,
+    /// but the groups may overlap and are not necessarily the largest ones.
+    /// Every negative binary clause is covered by a group, a lone one giving a group of two variables.
+    pub fn detect_at_most_one(&self) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![BTreeSet::new(); self.num_vars()];
+        for clause in &self.clauses {
+            let clause = clause.canonical();
+            if let &[a, b] = clause.get_literals() {
+                if a.is_negated() && b.is_negated() && a.index() != b.index() {
+                    adjacency[a.index()].insert(b.index());
+                    adjacency[b.index()].insert(a.index());
+                }
+            }
+        }
+
+        let mut covered = HashSet::new();
+        let mut groups = vec![];
+        for (i, neighbors) in adjacency.iter().enumerate() {
+            for &j in neighbors.range(i + 1..) {
+                if covered.contains(&(i, j)) {
+                    continue;
+                }
+
+                let mut group = vec![i, j];
+                for &k in neighbors {
+                    if k != j && group.iter().all(|&v| adjacency[v].contains(&k)) {
+                        group.push(k);
+                    }
+                }
+                group.sort_unstable();
+
+                for (a, &u) in group.iter().enumerate() {
+                    for &v in &group[a + 1..] {
+                        covered.insert((u, v));
+                    }
+                }
+                groups.push(group);
+            }
+        }
+
+        groups
+    }
+
+    /// Returns the `m × m` matrix of the number of distinct variables shared by each pair of clauses,
+    /// the dual of `cooccurrence`. The matrix is symmetric, and its diagonal holds the number of distinct variables of each clause.
+    /// It is built in O(m² · k) time and O(m²) memory for `m` clauses of length `k`, so it is only meant for small instances.
+    pub fn clause_overlap(&self) -> Vec<Vec<usize>> {
+        let var_sets: Vec<_> = self.clauses.iter().map(Clause::var_set).collect();
+        let mut overlap = vec![vec![0; var_sets.len()]; var_sets.len()];
+
+        for (i, a) in var_sets.iter().enumerate() {
+            for (j, b) in var_sets.iter().enumerate().skip(i) {
+                let shared = a.intersection(b).count();
+                overlap[i][j] = shared;
+                overlap[j][i] = shared;
+            }
+        }
+
+        overlap
+    }
+
+    /// Returns the variables ranked by activity, most active first, ties broken by increasing index.
+    /// The activity of a variable is the sum of `2^-len` over its occurrences in clauses of length `len`,
+    /// so that short clauses weigh more. Unlike Jeroslow-Wang, both polarities add to the same score.
+    /// Variables appearing in no clause are ranked last with an activity of 0.
+    pub fn variable_activity(&self) -> Vec<(usize, f64)> {
+        let mut activity = vec![0.0; self.num_vars()];
+        for clause in &self.clauses {
+            let weight = 0.5f64.powi(clause.len() as i32);
+            for lit in clause.get_literals() {
+                activity[lit.index()] += weight;
+            }
+        }
+
+        let mut ranking: Vec<_> = activity.into_iter().enumerate().collect();
+        ranking.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        ranking
+    }
+
+    /// Returns an instance equisatisfiable with the negation of this one.
+    /// A selector variable is added for each clause: the variable `num_vars() + j` implies that the clause `j` is falsified,
+    /// and a last clause requires one of the selectors to be true.
+    /// The models of the result, restricted to the original variables, are exactly the assignments falsifying this instance.
+    pub fn tseitin_negation(&self) -> Instance {
+        let n = self.num_vars();
+
+        let mut clauses: Vec<Clause> = self
+            .clauses
+            .iter()
+            .enumerate()
+            .flat_map(|(j, clause)| {
+                let selector = Literal::new(n + j, true);
+                clause
+                    .get_literals()
+                    .iter()
+                    .map(move |elem| Clause::from_iter([selector, elem.negated()]))
+            })
+            .collect();
+        clauses.push(Clause::from_indices(
+            n..n + self.clauses.len(),
+            std::iter::repeat(false),
+        ));
+
+        Self::with_clauses(n + self.clauses.len(), clauses)
+    }
+
+    /// Returns the clauses of the sequential counter encoding of "at least `k` of the variables `vars` are true",
+    /// along with the number of auxiliary variables they introduce. The clauses are not added to the instance.
+    ///
+    /// The constraint is encoded as "at most `n - k` of the negated variables are true" (Sinz, 2005),
+    /// with `(n - 1) * (n - k)` auxiliary variables for `n = vars.len()` and `k <= n`, numbered from `num_vars()`:
+    /// the auxiliary variable `num_vars() + i * (n - k) + j` is forced true when at least `j + 1`
+    /// of the first `i + 1` variables are false.
+    /// Without auxiliary variables, `k = 0` gives no clause, `k = n` gives unit clauses,
+    /// and `k > n` gives the empty clause.
+    pub fn at_least_k(&self, vars: &[usize], k: usize) -> (Vec<Clause>, usize) {
+        let n = vars.len();
+        if k == 0 {
+            return (vec![], 0);
+        }
+        if k > n {
+            return (vec![Clause::new(vec![])], 0);
+        }
+
+        let m = n - k;
+        if m == 0 {
+            let units = vars
+                .iter()
+                .map(|&var| Clause::from_iter([Literal::new(var, false)]))
+                .collect();
+            return (units, 0);
+        }
+
+        let base = self.num_vars();
+        // s(i, j): at least j + 1 of the first i + 1 variables are false
+        let s = |i: usize, j: usize| base + i * m + j;
+        // Negation of "the i-th variable is false"
+        let x = |i: usize| Literal::new(vars[i], false);
+        let pos = |var: usize| Literal::new(var, false);
+        let neg = |var: usize| Literal::new(var, true);
+
+        let mut clauses = vec![Clause::from_iter([x(0), pos(s(0, 0))])];
+        clauses.extend((1..m).map(|j| Clause::from_iter([neg(s(0, j))])));
+
+        for i in 1..n - 1 {
+            clauses.push(Clause::from_iter([x(i), pos(s(i, 0))]));
+            clauses.push(Clause::from_iter([neg(s(i - 1, 0)), pos(s(i, 0))]));
+            for j in 1..m {
+                clauses.push(Clause::from_iter([
+                    x(i),
+                    neg(s(i - 1, j - 1)),
+                    pos(s(i, j)),
+                ]));
+                clauses.push(Clause::from_iter([neg(s(i - 1, j)), pos(s(i, j))]));
+            }
+            clauses.push(Clause::from_iter([x(i), neg(s(i - 1, m - 1))]));
+        }
+        clauses.push(Clause::from_iter([x(n - 1), neg(s(n - 2, m - 1))]));
+
+        (clauses, (n - 1) * m)
+    }
+
+    /// Returns the edges of the implication graph of the binary clauses: `(¬a → b)` and `(¬b → a)` for each clause `(a ∨ b)`.
+    /// The nodes are the `2n` literals.
+    pub fn implication_edges(&self) -> Vec<(Literal, Literal)> {
+        self.clauses
+            .iter()
+            .filter_map(|clause| match clause.get_literals() {
+                &[a, b] => Some([(-a, b), (-b, a)]),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Returns the pairs of variables `(representative, var)` proven equivalent by pairs of binary clauses
+    /// `(¬x ∨ y)` and `(x ∨ ¬y)`, sorted by `var`.
+    /// Equivalences are chained transitively with a union-find, each class being represented by its smallest variable,
+    /// so every variable of a class but the representative appears once as `var`.
+    pub fn find_equivalences(&self) -> Vec<(usize, usize)> {
+        let edges: HashSet<_> = self.implication_edges().into_iter().collect();
+
+        // x ≡ y when x → y and y → x, the edges between their negations coming from the same clauses
+        let mut sets = UnionFind::new(self.num_vars());
+        for &(a, b) in &edges {
+            if a.index() != b.index() && a.is_negated() == b.is_negated() && edges.contains(&(b, a))
+            {
+                sets.union(a.index(), b.index());
+            }
+        }
+
+        (0..self.num_vars())
+            .filter_map(|var| {
+                let representative = sets.find(var);
+                (representative != var).then_some((representative, var))
+            })
+            .collect()
+    }
+
+    /// Freezes the variable `var`, so that `eliminate_variable` and `eliminate_pure_literals` keep it,
+    /// as needed for the variables of assumptions or the ones the caller reads in the models.
+    /// Freezing refers to the variable index, it does not follow a renumbering of the variables.
+    /// Panics if `var` is not present in the variables.
+    pub fn freeze_var(&mut self, var: usize) {
+        assert!(var < self.num_vars(), "variable {var} out of bounds");
+        self.frozen.insert(var);
+    }
+
+    /// Returns whether the variable `var` is frozen, see `freeze_var`.
+    pub fn is_frozen(&self, var: usize) -> bool {
+        self.frozen.contains(&var)
+    }
+
+    /// Eliminates the variable `var` by resolution (Davis-Putnam): the clauses containing it are replaced
+    /// by their non-tautological resolvents on `var`, so that the instance stays equisatisfiable.
+    /// The variable no longer appears in the clauses but is kept in the variables, and a model of the result
+    /// extends to the original instance by choosing its value to satisfy the replaced clauses.
+    /// The number of clauses can grow quadratically. The clause labels and kinds are dropped.
+    /// Returns false, leaving the instance unchanged, if `var` is frozen.
+    pub fn eliminate_variable(&mut self, var: usize) -> bool {
+        if self.is_frozen(var) {
+            return false;
+        }
+
+        let pos = Literal::new(var, false);
+        let neg = Literal::new(var, true);
+        let (occurrences, mut clauses): (Vec<_>, Vec<_>) = self
+            .clauses
+            .drain(..)
+            .partition(|clause| clause.contains_var(var));
+        let (positive, negative): (Vec<_>, Vec<_>) = occurrences
+            .into_iter()
+            .filter(|clause| !(clause.is_satisfied_by(pos) && clause.is_satisfied_by(neg)))
+            .partition(|clause| clause.is_satisfied_by(pos));
+
+        for a in &positive {
+            for b in &negative {
+                if let Some(resolvent) = a.resolve(b, var) {
+                    if !resolvent.is_tautology() {
+                        clauses.push(resolvent);
+                    }
+                }
+            }
+        }
+
+        self.clauses = clauses;
+        self.clear_clause_metadata();
+        self.touch();
+        true
+    }
+
+    /// Removes the clauses containing a pure literal, a literal whose negation appears in no clause,
+    /// until no pure literal is left, frozen variables excepted.
+    /// The pure literals are set true in the variables, so that a model of the result is a model of the original instance.
+    /// Returns the number of variables eliminated. The clause labels and kinds are dropped if a clause is removed.
+    pub fn eliminate_pure_literals(&mut self) -> usize {
+        let mut eliminated = 0;
+
+        loop {
+            let index = self.occurrence_index();
+            let pure: Vec<_> = (0..self.num_vars())
+                .filter(|&var| !self.is_frozen(var))
+                .filter_map(|var| match index.polarity(var) {
+                    VarPolarity::PositiveOnly => Some(Literal::new(var, false)),
+                    VarPolarity::NegativeOnly => Some(Literal::new(var, true)),
+                    _ => None,
+                })
+                .collect();
+            if pure.is_empty() {
+                break;
+            }
+
+            for lit in &pure {
+                self.vars.set(lit.index(), !lit.is_negated());
+            }
+            self.clauses
+                .retain(|clause| !pure.iter().any(|&lit| clause.is_satisfied_by(lit)));
+            eliminated += pure.len();
+        }
+
+        if eliminated > 0 {
+            self.clear_clause_metadata();
+            self.touch();
+        }
+        eliminated
+    }
+
+    /// Substitutes each variable by the representative of its equivalence class (see `find_equivalences`),
+    /// then removes the clauses turned into tautologies and the repeated literals.
+    /// The substituted variables no longer appear in the clauses, but are kept in the variables.
+    /// The clause labels and kinds are dropped.
+    pub fn merge_equivalences(&mut self) {
+        let mut representatives: Vec<usize> = (0..self.num_vars()).collect();
+        for (representative, var) in self.find_equivalences() {
+            representatives[var] = representative;
+        }
+
+        self.clauses = self
+            .clauses
+            .iter()
+            .map(|clause| {
+                let mut clause =
+                    clause.map(|lit| Literal::new(representatives[lit.index()], lit.is_negated()));
+                clause.dedup_literals();
+                clause
+            })
+            .filter(|clause| !clause.is_tautology())
+            .collect();
+        self.clear_clause_metadata();
+        self.touch();
+    }
+
+    /// Rewrites the instance in a normal form: tautologies are removed, the literals of each clause are sorted
+    /// and deduplicated (see `Clause::canonical`), repeated clauses are removed, and the clauses are sorted
+    /// in lexicographic order of their literals.
+    /// Instances equal up to the order of their clauses and literals and to these redundancies are written identically by `to_file`.
+    /// The clause labels and kinds are dropped.
+    pub fn canonicalize(&mut self) {
+        let mut clauses: Vec<_> = self
+            .clauses
+            .iter()
+            .filter(|clause| !clause.is_tautology())
+            .map(Clause::canonical)
+            .collect();
+        clauses.sort_by(|a, b| a.get_literals().cmp(b.get_literals()));
+        clauses.dedup();
+
+        self.clauses = clauses;
+        self.clear_clause_metadata();
+        self.touch();
+    }
+
+    /// Returns true if the instance is obviously unsatisfiable, that is if it contains
+    /// an empty clause or two contradictory unit clauses `(x)` and `(¬x)`.
+    /// Returning false does not mean the instance is satisfiable.
+    pub fn is_trivially_unsat(&self) -> bool {
+        let mut units = HashSet::new();
+
+        for clause in &self.clauses {
+            if clause.is_empty() {
+                return true;
+            }
+
+            if let Some(lit) = clause.unit_literal() {
+                if units.contains(&lit.negated()) {
+                    return true;
+                }
+                units.insert(lit);
+            }
+        }
+
+        false
+    }
+}
+
+/// Extends `term` with a literal of each clause it does not hit, without contradicting it and up to `max_len` literals,
+/// and collects in canonical form the terms hitting all clauses, see `Instance::prime_implicants_up_to`.
+fn collect_implicants(
+    clauses: &[&Clause],
+    max_len: usize,
+    term: &mut Vec<Literal>,
+    implicants: &mut HashSet<Clause>,
+) {
+    let unhit = clauses
+        .iter()
+        .find(|clause| !clause.get_literals().iter().any(|lit| term.contains(lit)));
+    let Some(clause) = unhit else {
+        implicants.insert(term.iter().copied().collect::<Clause>().canonical());
+        return;
+    };
+    if term.len() >= max_len {
+        return;
+    }
+
+    for &lit in clause.canonical().get_literals() {
+        if !term.contains(&lit.negated()) {
+            term.push(lit);
+            collect_implicants(clauses, max_len, term, implicants);
+            term.pop();
+        }
+    }
+}
+
+/// Reads the comment lines and the header of a CNF, see `Instance::read_header`.
+fn read_header_from<R: BufRead>(reader: &mut R) -> Result<(usize, usize), CnfParseError> {
+    let options = ParseOptions::default();
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(CnfParseError::Header);
+        }
+        if !line.trim().is_empty() && !line.starts_with(options.comment_char) {
+            return parse_header(&mut std::iter::once(line.as_str()), &options)
+                .ok_or(CnfParseError::Header);
+        }
+    }
+}
+
+/// Collects the clauses into an instance, see `Instance::from_clauses`.
+impl FromIterator<Clause> for Instance {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = Clause>,
+    {
+        Self::from_clauses(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::literal::LiteralBase;
+
+    #[test]
+    fn test() {}
+
+    #[test]
+    fn trivially_unsat() {
+        let sat = Instance::with_clauses(
+            2,
+            vec![Clause::from_cnf(vec![1]), Clause::from_cnf(vec![-1, 2])],
+        );
+        assert!(!sat.is_trivially_unsat());
+
+        let empty = Instance::with_clauses(
+            2,
+            vec![Clause::from_cnf(vec![1, 2]), Clause::from_cnf(vec![])],
+        );
+        assert!(empty.is_trivially_unsat());
+
+        let conflicting_units = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![-2]),
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![2]),
+            ],
+        );
+        assert!(conflicting_units.is_trivially_unsat());
+    }
+
+    #[test]
+    fn propagate_and_simplify() {
+        let mut instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![1]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![-2, 3, 4]),
+                Clause::from_cnf(vec![1, 4]),
+                Clause::from_cnf(vec![-3, -4]),
+            ],
+        );
+
+        assert_eq!(instance.propagate_and_simplify(), Ok(3));
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![Clause::from_cnf(vec![3, 4]), Clause::from_cnf(vec![-3, -4])]
+        );
+        assert_eq!(instance.vars.into_vec(), vec![true, true, false, false]);
+
+        let mut unsat = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![-1, -2]),
+            ],
+        );
+        assert_eq!(unsat.propagate_and_simplify(), Err(()));
+
+        let mut empty = Instance::with_clauses(1, vec![Clause::from_cnf(vec![])]);
+        assert_eq!(empty.propagate_and_simplify(), Err(()));
+    }
+
+    #[test]
+    fn simplify_under() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![-1, 2, 3]),
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![2, 3]),
+            ],
+        );
+        let mut a = Assignment::new(3);
+        a.set(0, true);
+
+        let residual = instance.simplify_under(&a);
+        assert_eq!(residual.num_vars(), 3);
+        assert_eq!(
+            residual.get_clauses(),
+            &vec![
+                Clause::from_cnf(vec![2, 3]),
+                Clause::from_cnf(vec![]),
+                Clause::from_cnf(vec![2, 3]),
+            ]
+        );
+        assert_eq!(
+            instance.simplify_under(&Assignment::new(3)).get_clauses(),
+            instance.get_clauses()
+        );
+    }
+
+    #[test]
+    fn unit_propagation() {
+        let mut instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![3]),
+                Clause::from_cnf(vec![3]),
+            ],
+        );
+
+        assert_eq!(
+            instance.unit_literals(),
+            vec![
+                Literal::from_cnf(-1),
+                Literal::from_cnf(3),
+                Literal::from_cnf(3)
+            ]
+        );
+        instance.vars.set(0, true).unwrap();
+        assert_eq!(instance.propagate_units_into_vars(), Ok(2));
+        assert_eq!(instance.vars, BoolVec::from([false, false, true]));
+
+        let mut conflicting = Instance::with_clauses(
+            1,
+            vec![Clause::from_cnf(vec![1]), Clause::from_cnf(vec![-1])],
+        );
+        assert_eq!(
+            conflicting.propagate_units_into_vars(),
+            Err(Literal::from_cnf(-1))
+        );
+    }
+
+    #[test]
+    fn heap_size() {
+        let clauses = vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![2])];
+        let clauses_size = clauses.capacity() * std::mem::size_of::<Clause>()
+            + clauses.iter().map(Clause::heap_size).sum::<usize>();
+        let instance = Instance::with_clauses(16, clauses);
+
+        assert_eq!(instance.heap_size(), 2 + clauses_size);
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut clauses = Vec::with_capacity(100);
+        clauses.push(Clause::from_cnf(vec![1, -2]));
+        let mut instance = Instance::with_clauses(2, clauses);
+
+        let before = instance.heap_size();
+        instance.shrink_to_fit();
+
+        assert!(instance.heap_size() < before);
+        assert_eq!(instance.get_clauses(), &vec![Clause::from_cnf(vec![1, -2])]);
+    }
+
+    #[test]
+    fn reader() {
+        let cnf = "c comment\np cnf 3 2\n1 -2 0\n-3 0\n";
+        let instance = Instance::from_reader(cnf.as_bytes()).unwrap();
+
+        assert_eq!(instance.num_vars(), 3);
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![-3])]
+        );
+
+        assert!(Instance::from_reader("p cnf 3 1\n1 x 0\n".as_bytes()).is_err());
+        assert!(Instance::from_reader("p dnf 3 0\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn read_header() {
+        let path = std::env::temp_dir().join(format!("sat_lab_header_{}.cnf", std::process::id()));
+
+        fs::write(&path, "c comment\nc another\np cnf 3 2\nnot a clause\n").unwrap();
+        assert_eq!(Instance::read_header(&path).unwrap(), (3, 2));
+
+        fs::write(&path, "c comment only\n").unwrap();
+        assert!(matches!(
+            Instance::read_header(&path),
+            Err(CnfParseError::Header)
+        ));
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            Instance::read_header(&path),
+            Err(CnfParseError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn reader_zero_based() {
+        let cnf = "p cnf 3 2\n0 -1 99\n-0 2 99\n";
+        let options = ParseOptions {
+            base: LiteralBase::Zero { terminator: 99 },
+            ..Default::default()
+        };
+        let instance = Instance::from_reader_with(cnf.as_bytes(), &options).unwrap();
+
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![-1, 3])]
+        );
+
+        let out_of_range = format!("p cnf 1 1\n{} 99\n", isize::MAX);
+        assert!(Instance::from_reader_with(out_of_range.as_bytes(), &options).is_err());
+    }
+
+    #[cfg(feature = "small-literals")]
+    #[test]
+    fn reader_literal_out_of_range() {
+        let cnf = "p cnf 1 1\n3000000000 0\n";
+        assert!(Instance::from_reader(cnf.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn resolution() {
+        let instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![2]),
+            ],
+        );
+        // (x1) is derived from (x0 ∨ x1) and (¬x0 ∨ x1), but is already in the instance
+        assert!(instance.resolution_step().is_empty());
+
+        let mut unsat = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![-1, -2]),
+            ],
+        );
+        assert!(unsat.saturate_resolution(100));
+        assert!(unsat.is_trivially_unsat());
+
+        let mut sat = Instance::with_clauses(
+            2,
+            vec![Clause::from_cnf(vec![1, 2]), Clause::from_cnf(vec![-1, 2])],
+        );
+        assert!(!sat.saturate_resolution(100));
+        assert_eq!(sat.get_clauses().len(), 3);
+
+        let mut clashing = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, -1, 2]),
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![-2]),
+            ],
+        );
+        assert!(!clashing.saturate_resolution(100));
+    }
+
+    #[test]
+    fn resolvent_count() {
+        let instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![1, -3]),
+                Clause::from_cnf(vec![-1, -2]),
+                Clause::from_cnf(vec![-1, 4]),
+                Clause::from_cnf(vec![3, 4]),
+            ],
+        );
+
+        // [1, 2] x [-1, -2] is a tautology
+        assert_eq!(instance.resolvent_count(0), 3);
+        assert_eq!(instance.resolvent_count(2), 1);
+        assert_eq!(instance.resolvent_count(3), 0);
+    }
+
+    #[test]
+    fn cooccurrence() {
+        let instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![1, -2, 3]),
+                Clause::from_cnf(vec![-1, 2, -1]),
+                Clause::from_cnf(vec![4]),
+            ],
+        );
+
+        assert_eq!(
+            instance.cooccurrence(),
+            HashMap::from([((0, 1), 2), ((0, 2), 1), ((1, 2), 1)])
+        );
+    }
+
+    #[test]
+    fn prime_implicants() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 3]),
+                Clause::from_cnf(vec![2, -2]),
+            ],
+        );
+        let primes: HashSet<_> = instance.prime_implicants().into_iter().collect();
+        let expected: HashSet<_> = [vec![1, 3], vec![2, -1], vec![2, 3]]
+            .into_iter()
+            .map(|term| Clause::from_cnf(term).canonical())
+            .collect();
+        assert_eq!(primes, expected);
+        assert!(instance.prime_implicants_up_to(1).is_empty());
+
+        // [1, 2] is absorbed by [1]
+        let absorbed = Instance::with_clauses(
+            2,
+            vec![Clause::from_cnf(vec![1, 2]), Clause::from_cnf(vec![1, -2])],
+        );
+        assert_eq!(absorbed.prime_implicants(), vec![Clause::from_cnf(vec![1])]);
+
+        assert_eq!(
+            Instance::with_clauses(1, vec![]).prime_implicants(),
+            vec![Clause::from_cnf(vec![])]
+        );
+        let unsat = Instance::with_clauses(
+            1,
+            vec![Clause::from_cnf(vec![1]), Clause::from_cnf(vec![-1])],
+        );
+        assert!(unsat.prime_implicants().is_empty());
+    }
+
+    #[test]
+    fn detect_at_most_one() {
+        let mut clauses = vec![];
+        for i in 1..=4 {
+            for j in i + 1..=4 {
+                clauses.push(Clause::from_cnf(vec![-i, -j]));
+            }
+        }
+        clauses.push(Clause::from_cnf(vec![-5, -4]));
+        clauses.push(Clause::from_cnf(vec![2, 6]));
+        clauses.push(Clause::from_cnf(vec![-6, -6]));
+        clauses.push(Clause::from_cnf(vec![-1, -5, -6]));
+        let instance = Instance::with_clauses(6, clauses);
+
+        assert_eq!(
+            instance.detect_at_most_one(),
+            vec![vec![0, 1, 2, 3], vec![3, 4]]
+        );
+        assert!(
+            Instance::with_clauses(2, vec![Clause::from_cnf(vec![1, -2])])
+                .detect_at_most_one()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn literal_impact() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 3]),
+                Clause::from_cnf(vec![2, 3]),
+                Clause::from_cnf(vec![1, -1]),
+                Clause::from_cnf(vec![1]),
+            ],
+        );
+
+        assert_eq!(
+            instance.literal_impact(Literal::from_cnf(1)),
+            (vec![0, 3, 4], vec![1, 3])
+        );
+        assert_eq!(
+            instance.literal_impact(Literal::from_cnf(-2)),
+            (vec![], vec![0, 2])
+        );
+    }
+
+    #[test]
+    fn degree_sequence() {
+        let instance = Instance::with_clauses(
+            5,
+            vec![
+                Clause::from_cnf(vec![1, -2, 3]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![3, 4, 4]),
+                Clause::from_cnf(vec![-4, 4]),
+            ],
+        );
+
+        assert_eq!(instance.degree_sequence(), vec![2, 2, 3, 1, 0]);
+    }
+
+    #[test]
+    fn clause_overlap() {
+        let instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![1, -2, 3]),
+                Clause::from_cnf(vec![-1, 2, -1]),
+                Clause::from_cnf(vec![4]),
+            ],
+        );
+
+        assert_eq!(
+            instance.clause_overlap(),
+            vec![vec![3, 2, 0], vec![2, 2, 0], vec![0, 0, 1]]
+        );
+    }
+
+    #[test]
+    fn variable_activity() {
+        let instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![1, -2, 3]),
+                Clause::from_cnf(vec![-2, -3]),
+                Clause::from_cnf(vec![-1]),
+            ],
+        );
+
+        assert_eq!(
+            instance.variable_activity(),
+            vec![(0, 0.625), (1, 0.375), (2, 0.375), (3, 0.0)]
+        );
+    }
+
+    #[test]
+    fn tseitin_negation() {
+        let mut instance = Instance::with_clauses(
+            2,
+            vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![2])],
+        );
+        let mut negation = instance.tseitin_negation();
+        assert_eq!(negation.num_vars(), 4);
+
+        for x in 0..4 {
+            instance.vars = BoolVec::from([x & 1 != 0, x & 2 != 0]);
+
+            let negation_sat = (0..4).any(|s| {
+                negation.vars = BoolVec::from([x & 1 != 0, x & 2 != 0, s & 1 != 0, s & 2 != 0]);
+                negation.is_sat()
+            });
+            assert_eq!(negation_sat, !instance.is_sat());
+        }
+
+        let empty = Instance::with_clauses(1, vec![]).tseitin_negation();
+        assert!(empty.is_trivially_unsat());
+    }
+
+    #[test]
+    fn reader_options() {
+        let cnf = "% comment\np wcnf 3 2\n1 -2 0\n-3\n";
+        assert!(Instance::from_reader(cnf.as_bytes()).is_err());
+
+        let options = ParseOptions {
+            comment_char: '%',
+            require_terminator: false,
+            problem_type: "wcnf".to_string(),
+            ..Default::default()
+        };
+        let instance = Instance::from_reader_with(cnf.as_bytes(), &options).unwrap();
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![-3])]
+        );
+
+        let unterminated = "p cnf 2 1\n1 -2\n";
+        assert_eq!(
+            Instance::from_reader(unterminated.as_bytes())
+                .unwrap()
+                .get_clauses(),
+            &vec![Clause::from_cnf(vec![1, -2])]
+        );
+
+        let options = ParseOptions {
+            require_terminator: true,
+            ..Default::default()
+        };
+        assert!(Instance::from_reader_with(unterminated.as_bytes(), &options).is_err());
+    }
+
+    #[test]
+    fn reader_collecting() {
+        let cnf = "c comment\np cnf 3 4\na 1 0\ne x 0\n1 -2 0\n1 y 0\n-5 0\n2 -z 0\n";
+        let errors = Instance::from_reader_collecting(cnf.as_bytes()).unwrap_err();
+        assert!(matches!(
+            errors[..],
+            [
+                CnfParseError::Quantifier { line: 4 },
+                CnfParseError::Clause { line: 6 },
+                CnfParseError::UndeclaredVar { line: 7 },
+                CnfParseError::Clause { line: 8 },
+            ]
+        ));
+
+        let cnf = "p cnf 2 2\n1 -2 0\n2 0\n";
+        let instance = Instance::from_reader_collecting(cnf.as_bytes()).unwrap();
+        assert_eq!(
+            instance.get_clauses(),
+            Instance::from_reader(cnf.as_bytes()).unwrap().get_clauses()
+        );
+
+        let errors =
+            Instance::from_reader_collecting("p cnf 2 1\ne 9 0\n1 2 0\n".as_bytes()).unwrap_err();
+        assert!(matches!(
+            errors[..],
+            [CnfParseError::UndeclaredVar { line: 2 }]
+        ));
+
+        let cnf = "\n\np cnf 2 1\n1 x 0\n";
+        let errors = Instance::from_reader_collecting(cnf.as_bytes()).unwrap_err();
+        assert!(matches!(errors[..], [CnfParseError::Clause { line: 4 }]));
+
+        let errors = Instance::from_reader_collecting("1 -2 0\n".as_bytes()).unwrap_err();
+        assert!(matches!(errors[..], [CnfParseError::Header]));
+    }
+
+    #[test]
+    fn reader_clause_labels() {
+        let cnf = "p cnf 2 3\n1 -2 0 c: assert x => y\n2 0\n-1 0 c:nonzero\n";
+        let options = ParseOptions {
+            clause_labels: true,
+            ..Default::default()
+        };
+
+        let mut instance = Instance::from_reader_with(cnf.as_bytes(), &options).unwrap();
+        assert_eq!(instance.get_clauses()[0], Clause::from_cnf(vec![1, -2]));
+        assert_eq!(instance.clause_label(0), Some("assert x => y"));
+        assert_eq!(instance.clause_label(1), None);
+        assert_eq!(instance.clause_label(2), Some("nonzero"));
+        assert_eq!(instance.clause_label(3), None);
+
+        instance.remove_clause(0);
+        instance.add_clause(Clause::from_cnf(vec![1]));
+        assert_eq!(instance.clause_label(1), Some("nonzero"));
+        assert_eq!(instance.clause_label(2), None);
+
+        let ignored = Instance::from_reader(cnf.as_bytes()).unwrap();
+        let labeled = Instance::from_reader_with(cnf.as_bytes(), &options).unwrap();
+        assert_eq!(ignored.get_clauses(), labeled.get_clauses());
+        assert_eq!(ignored.clause_label(0), None);
+    }
+
+    #[test]
+    fn reader_grow_vars() {
+        let cnf = "p cnf 2 2\n1 -2 0\n-4 0\n";
+        assert!(Instance::from_reader(cnf.as_bytes()).is_err());
+
+        let options = ParseOptions {
+            grow_vars: true,
+            ..Default::default()
+        };
+        let instance = Instance::from_reader_with(cnf.as_bytes(), &options).unwrap();
+        assert_eq!(instance.num_vars(), 4);
+        assert_eq!(instance.get_clauses().len(), 2);
+
+        let (_, warnings) = Instance::from_reader_with_warnings(cnf.as_bytes(), &options).unwrap();
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::VarCount {
+                declared: 2,
+                found: 4
+            }]
+        );
+        let (_, warnings) =
+            Instance::from_reader_with_warnings("p cnf 2 1\n1 -2 0\n".as_bytes(), &options)
+                .unwrap();
+        assert!(warnings.is_empty());
+
+        let quantified = "p cnf 2 1\ne 3 0\n1 -2 0\n";
+        assert!(Instance::from_reader(quantified.as_bytes()).is_err());
+        assert!(Instance::from_reader_with(quantified.as_bytes(), &options).is_err());
+        let quantified = "p cnf 2 1\ne 3 0\n1 -3 0\n";
+        let instance = Instance::from_reader_with(quantified.as_bytes(), &options).unwrap();
+        assert_eq!(instance.quantifiers(), &[(Quantifier::Exists, vec![2])]);
+
+        let (instance, warnings) = Instance::from_reader_lossy(cnf.as_bytes()).unwrap();
+        assert_eq!(instance.num_vars(), 4);
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::VarCount {
+                declared: 2,
+                found: 4
+            }]
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn fuzz() {
+        use rand::rngs::StdRng;
+
+        let strict = Instance::new_fuzz(4, 200, 6, &mut StdRng::seed_from_u64(0), false, false);
+        assert_eq!(strict.get_clauses().len(), 200);
+        for clause in strict.get_clauses() {
+            assert!((1..=4).contains(&clause.len()));
+            assert_eq!(clause.var_set().len(), clause.len());
+        }
+
+        let lenient = Instance::new_fuzz(4, 200, 6, &mut StdRng::seed_from_u64(0), true, true);
+        assert!(lenient.get_clauses().iter().any(Clause::is_empty));
+        assert!(lenient.get_clauses().iter().any(|c| c.len() > 4));
+
+        let again = Instance::new_fuzz(4, 200, 6, &mut StdRng::seed_from_u64(0), true, true);
+        assert_eq!(lenient.get_clauses(), again.get_clauses());
+        assert_eq!(lenient.vars, again.vars);
+
+        let no_vars = Instance::new_fuzz(0, 10, 3, &mut StdRng::seed_from_u64(0), false, true);
+        assert!(no_vars.get_clauses().iter().all(Clause::is_empty));
+        assert!(
+            Instance::new_fuzz(0, 0, 3, &mut StdRng::seed_from_u64(0), false, false)
+                .get_clauses()
+                .is_empty()
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    #[should_panic]
+    fn fuzz_panic() {
+        use rand::rngs::StdRng;
+
+        Instance::new_fuzz(0, 1, 3, &mut StdRng::seed_from_u64(0), false, false);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn community() {
+        use rand::rngs::StdRng;
+
+        let community = |var: usize| var / 5;
+        let intra = Instance::new_community(4, 5, 100, 3, 0.0, &mut StdRng::seed_from_u64(0));
+        assert_eq!(intra.num_vars(), 20);
+        assert_eq!(intra.get_clauses().len(), 100);
+        for clause in intra.get_clauses() {
+            let vars = clause.var_set();
+            assert_eq!(vars.len(), 3);
+            assert!(vars
+                .iter()
+                .all(|&var| community(var) == community(clause.get_literals()[0].index())));
+        }
+
+        let inter = Instance::new_community(4, 5, 100, 3, 1.0, &mut StdRng::seed_from_u64(0));
+        assert!(inter.get_clauses().iter().any(|clause| {
+            let communities: HashSet<_> = clause.var_set().into_iter().map(community).collect();
+            communities.len() > 1
+        }));
+    }
+
+    #[test]
+    fn clauses() {
+        let clauses = vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![2])];
+        let instance = Instance::with_clauses(2, clauses.clone());
+
+        assert!(instance.clauses().eq(clauses.iter()));
+        assert_eq!(instance.into_clauses(), clauses);
+    }
+
+    #[test]
+    fn from_clauses() {
+        let clauses = vec![Clause::from_cnf(vec![1, -4]), Clause::from_cnf(vec![2])];
+
+        let instance = Instance::from_clauses(clauses.clone());
+        assert_eq!(instance.num_vars(), 4);
+        assert_eq!(instance.get_clauses(), &clauses);
+
+        let collected: Instance = clauses.iter().cloned().collect();
+        assert_eq!(collected.num_vars(), 4);
+        assert_eq!(Instance::from_clauses([]).num_vars(), 0);
+    }
+
+    #[test]
+    fn enumerate_clauses() {
+        let clauses = vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![2])];
+        let instance = Instance::with_clauses(2, clauses.clone());
+
+        assert_eq!(
+            instance.enumerate_clauses().collect::<Vec<_>>(),
+            vec![(0, &clauses[0]), (1, &clauses[1])]
+        );
+    }
+
+    #[test]
+    fn clauses_mut() {
+        let mut instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, 1, -2]),
+                Clause::from_cnf(vec![2, 2]),
+            ],
+        );
+        let generation = instance.generation();
+        instance.clauses_mut().for_each(Clause::dedup_literals);
+
+        assert_ne!(instance.generation(), generation);
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![2])]
+        );
+    }
+
+    #[test]
+    fn reader_lossy() {
+        let cnf = "c comment\np cnf 2 2\n1 -2 0\n\n2 0\nc inner comment\n-3\n";
+        let (instance, warnings) = Instance::from_reader_lossy(cnf.as_bytes()).unwrap();
+
+        assert_eq!(instance.num_vars(), 3);
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![2]),
+                Clause::from_cnf(vec![-3])
+            ]
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                ParseWarning::BlankLine { line: 4 },
+                ParseWarning::MissingTerminator { line: 7 },
+                ParseWarning::ClauseCount {
+                    declared: 2,
+                    found: 3
+                },
+                ParseWarning::VarCount {
+                    declared: 2,
+                    found: 3
+                },
+            ]
+        );
+
+        let (_, warnings) = Instance::from_reader_lossy("p cnf 2 1\n1 -2 0\n".as_bytes()).unwrap();
+        assert!(warnings.is_empty());
+        assert!(Instance::from_reader_lossy("p cnf 2 1\n1 x 0\n".as_bytes()).is_err());
+
+        let qdimacs = "p cnf 2 1\ne 1 0\na 2 0\n1 2 0\n";
+        let (instance, warnings) = Instance::from_reader_lossy(qdimacs.as_bytes()).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(instance.get_clauses(), &vec![Clause::from_cnf(vec![1, 2])]);
+        assert_eq!(
+            instance.quantifiers(),
+            Instance::from_reader(qdimacs.as_bytes())
+                .unwrap()
+                .quantifiers()
+        );
+
+        let (instance, warnings) =
+            Instance::from_reader_lossy("p cnf 2 1\ne 3 0\n1 2 0\n".as_bytes()).unwrap();
+        assert_eq!(instance.num_vars(), 3);
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::VarCount {
+                declared: 2,
+                found: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn subformula() {
+        let mut instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![2]),
+                Clause::from_cnf(vec![-3]),
+            ],
+        );
+        instance.vars.set(2, true).unwrap();
+
+        let sub = instance.subformula(&[2, 0]);
+        assert_eq!(sub.vars, instance.vars);
+        assert_eq!(
+            sub.get_clauses(),
+            &vec![Clause::from_cnf(vec![-3]), Clause::from_cnf(vec![1, -2])]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn subformula_panic() {
+        Instance::with_clauses(1, vec![Clause::from_cnf(vec![1])]).subformula(&[1]);
+    }
+
+    #[test]
+    fn summary_line() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![3])],
+        );
+
+        assert_eq!(
+            instance.summary_line(),
+            "vars=3 clauses=2 sat=1 ratio=0.667"
+        );
+    }
+
+    #[test]
+    fn at_least_k() {
+        let instance = Instance::with_clauses(5, vec![]);
+        let vars = [0, 2, 3, 4];
+
+        for k in 0..=5 {
+            let (clauses, aux) = instance.at_least_k(&vars, k);
+            let encoded = Instance::with_clauses(5 + aux, clauses);
+
+            for x in 0..32usize {
+                let assumptions: Vec<_> =
+                    (0..5).map(|i| Literal::new(i, x & (1 << i) == 0)).collect();
+                let count = vars.iter().filter(|&&i| x & (1 << i) != 0).count();
+                assert_eq!(
+                    encoded.solve_under_assumptions(&assumptions).is_sat(),
+                    count >= k,
+                    "k={k} x={x:05b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn implication_edges() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![1, 2, 3]),
+                Clause::from_cnf(vec![-3, 2]),
+            ],
+        );
+
+        assert_eq!(
+            instance.implication_edges(),
+            [(-1, -2), (2, 1), (3, 2), (-2, -3)]
+                .map(|(a, b)| (Literal::from_cnf(a), Literal::from_cnf(b)))
+        );
+    }
+
+    #[test]
+    fn eliminate_variable() {
+        let mut instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 3]),
+                Clause::from_cnf(vec![1, -1, 4]),
+                Clause::from_cnf(vec![2, 4]),
+                Clause::from_cnf(vec![-1, -2]),
+            ],
+        );
+        let original = instance.clone();
+
+        instance.freeze_var(0);
+        assert!(instance.is_frozen(0));
+        assert!(!instance.eliminate_variable(0));
+        assert_eq!(instance.get_clauses(), original.get_clauses());
+
+        let mut instance = original.clone();
+        assert!(instance.eliminate_variable(0));
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![Clause::from_cnf(vec![2, 4]), Clause::from_cnf(vec![2, 3])]
+        );
+        assert!(instance.equisatisfiable(&original));
+    }
+
+    #[test]
+    fn eliminate_pure_literals() {
+        let clauses = vec![
+            Clause::from_cnf(vec![1, -2]),
+            Clause::from_cnf(vec![2, 3]),
+            Clause::from_cnf(vec![-3, 4]),
+        ];
+
+        let mut instance = Instance::with_clauses(4, clauses.clone());
+        assert_eq!(instance.eliminate_pure_literals(), 4);
+        assert!(instance.get_clauses().is_empty());
+
+        // Without x4, the literals become pure one after the other
+        let mut instance = Instance::with_clauses(4, clauses);
+        instance.freeze_var(3);
+        assert_eq!(instance.eliminate_pure_literals(), 3);
+        assert!(instance.get_clauses().is_empty());
+        assert_eq!(instance.vars.into_vec(), vec![true, true, false, false]);
+        assert_eq!(instance.eliminate_pure_literals(), 0);
+    }
+
+    #[test]
+    fn equivalences() {
+        let mut instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![-3, 2]),
+                Clause::from_cnf(vec![-2, 3]),
+                Clause::from_cnf(vec![3, 4]),
+                Clause::from_cnf(vec![-4, 1, 3]),
+                Clause::from_cnf(vec![-1, -4]),
+            ],
+        );
+
+        assert_eq!(instance.find_equivalences(), vec![(0, 1), (0, 2)]);
+
+        instance.merge_equivalences();
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![
+                Clause::from_cnf(vec![1, 4]),
+                Clause::from_cnf(vec![-4, 1]),
+                Clause::from_cnf(vec![-1, -4]),
+            ]
+        );
+        assert!(instance.find_equivalences().is_empty());
+    }
+
+    #[test]
+    fn count_sat_bits() {
+        let mut instance = Instance::with_clauses(
+            70,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![-1, 70]),
+                Clause::from_cnf(vec![2, 69]),
+                Clause::from_cnf(vec![-70]),
+            ],
+        );
+        let bits = [0b01, 1 << 5];
+
+        instance.set_model(BoolVec::from(
+            (0..70)
+                .map(|i| Literal::new(i, false).eval_bits(&bits))
+                .collect::<Vec<_>>(),
+        ));
+        assert_eq!(instance.count_sat_bits(&bits), 2);
+        assert_eq!(instance.count_sat_bits(&bits), instance.count_sat());
+    }
+
+    #[test]
+    fn satisfaction_mask() {
+        let instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![2]),
+                Clause::from_cnf(vec![-1]),
+            ],
+        );
+
+        assert_eq!(
+            instance.satisfaction_mask(&BoolVec::from([true, false])),
+            BoolVec::from([true, false, false])
+        );
+    }
+
+    #[test]
+    fn satisfaction_breakdown() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 3]),
+                Clause::from_cnf(vec![1, -3]),
+                Clause::from_cnf(vec![-2]),
+            ],
+        );
+
+        let vars = BoolVec::from([true, true, false]);
+        assert_eq!(instance.satisfaction_breakdown(&vars), (2, 0, 2));
+        assert_eq!(
+            instance.satisfaction_breakdown(&BoolVec::from([true, false, true])),
+            (0, 4, 0)
+        );
+    }
+
+    #[test]
+    fn flip_scores() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![1, -3]),
+                Clause::from_cnf(vec![-1, 3]),
+                Clause::from_cnf(vec![-1, -2]),
+                Clause::from_cnf(vec![1, -1]),
+                Clause::from_cnf(vec![1, 1]),
+                Clause::from_cnf(vec![3]),
+            ],
+        );
+        let vars = BoolVec::from([true, false, false]);
+
+        // [1, -3] does not break as -3 stays true, [1, 1] breaks as 1 was its sole true literal
+        assert_eq!(instance.flip_scores(&vars, 0), (1, 2));
+        assert_eq!(instance.flip_scores(&vars, 1), (0, 1));
+        assert_eq!(instance.flip_scores(&vars, 2), (2, 0));
+
+        let index = instance.occurrence_index();
+        for var in 0..3 {
+            assert_eq!(
+                instance.flip_scores_with(&index, &vars, var),
+                instance.flip_scores(&vars, var)
+            );
+        }
+    }
+
+    #[test]
+    fn sat_count_delta() {
+        let instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 3]),
+                Clause::from_cnf(vec![-2, -3]),
+                Clause::from_cnf(vec![4]),
+                Clause::from_cnf(vec![-4, 1]),
+            ],
+        );
+        let count =
+            |vars: &BoolVec| instance.clauses().filter(|c| c.test_sat(vars)).count() as isize;
+
+        let a = BoolVec::from([true, false, false, false]);
+        let b = BoolVec::from([true, true, true, true]);
+        assert_eq!(instance.sat_count_delta(&a, &b), count(&b) - count(&a));
+        assert_eq!(instance.sat_count_delta(&b, &a), count(&a) - count(&b));
+        assert_eq!(instance.sat_count_delta(&a, &a), 0);
+
+        let mut flipped = BoolVec::from(a.into_vec());
+        flipped.set(2, true);
+        let (make, brk) = instance.flip_scores(&a, 2);
+        assert_eq!(
+            instance.sat_count_delta_with(&instance.occurrence_index(), &a, &flipped),
+            make as isize - brk as isize
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn flip_sensitivity() {
+        use rand::rngs::StdRng;
+
+        let instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![-1, -2]),
+                Clause::from_cnf(vec![1]),
+            ],
+        );
+        let counts = instance.flip_sensitivity(100, &mut StdRng::seed_from_u64(0));
+
+        // Flipping x0 breaks (¬x0), (¬x0 ∨ ¬x1) survives any single flip,
+        // and (x0) is unsatisfied to begin with
+        assert!(counts[0] > 0);
+        assert_eq!(counts[1], 0);
+        assert_eq!(counts[2], 0);
+    }
+
+    #[test]
+    fn writer_wrapped() {
+        let instance = Instance::with_clauses(
+            12,
+            vec![
+                Clause::from_cnf((1..=12).collect::<Vec<_>>()),
+                Clause::from_cnf(vec![-1]),
+            ],
+        );
+        let options = WriteOptions {
+            max_line_width: Some(8),
+        };
+
+        let mut out = vec![];
+        instance.to_writer_with(&mut out, &options).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out, "p cnf 12 2\n1 2 3 4\n5 6 7 8\n9 10 11\n12 0\n-1 0\n");
+
+        let read = Instance::from_reader(out.as_bytes()).unwrap();
+        assert_eq!(read.get_clauses(), instance.get_clauses());
+
+        let instance = Instance::with_clauses(
+            6,
+            vec![
+                Clause::from_cnf((1..=6).collect::<Vec<_>>()),
+                Clause::from_cnf(vec![-1]),
+            ],
+        );
+        let mut out = vec![];
+        instance
+            .to_writer_with(
+                &mut out,
+                &WriteOptions {
+                    max_line_width: Some(6),
+                },
+            )
+            .unwrap();
+        assert_eq!(out, b"p cnf 6 2\n1 2 3\n4 5 6\n0\n-1 0\n");
+
+        let read = Instance::from_reader(out.as_slice()).unwrap();
+        assert_eq!(read.get_clauses(), instance.get_clauses());
+        let read = Instance::from_reader_collecting(out.as_slice()).unwrap();
+        assert_eq!(read.get_clauses(), instance.get_clauses());
+        let (read, warnings) = Instance::from_reader_lossy(out.as_slice()).unwrap();
+        assert_eq!(read.get_clauses(), instance.get_clauses());
+        assert!(warnings.is_empty());
+        let (_, streamed) = Instance::stream_clauses(out.as_slice()).unwrap();
+        let streamed: Vec<_> = streamed.collect::<std::io::Result<_>>().unwrap();
+        assert_eq!(&streamed, instance.get_clauses());
+
+        let labeled = "p cnf 3 2\n1 -2\n3 0 c: wrapped\n-1 0\n";
+        let options = ParseOptions {
+            clause_labels: true,
+            ..Default::default()
+        };
+        let read = Instance::from_reader_with(labeled.as_bytes(), &options).unwrap();
+        assert_eq!(
+            read.get_clauses(),
+            &vec![Clause::from_cnf(vec![1, -2, 3]), Clause::from_cnf(vec![-1])]
+        );
+        assert_eq!(read.clause_label(0), Some("wrapped"));
+
+        let mut default = vec![];
+        instance
+            .to_writer_with(&mut default, &WriteOptions::default())
+            .unwrap();
+        let mut unwrapped = vec![];
+        instance.to_writer(&mut unwrapped).unwrap();
+        assert_eq!(default, unwrapped);
+        assert_eq!(String::from_utf8(default).unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn file_with_comments() {
+        let instance = Instance::with_clauses(2, vec![Clause::from_cnf(vec![1, -2])]);
+        let path =
+            std::env::temp_dir().join(format!("sat_lab_comments_{}.cnf", std::process::id()));
+
+        instance
+            .to_file_with_comments(&path, &["generator: test", "seed: 42\ntwo lines", ""])
+            .unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let read = Instance::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            content,
+            "c generator: test\nc seed: 42\nc two lines\nc\np cnf 2 1\n1 -2 0\n"
+        );
+        assert_eq!(read.get_clauses(), instance.get_clauses());
+    }
+
+    #[test]
+    fn file_atomic() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![3])],
+        );
+        let path = std::env::temp_dir().join(format!("sat_lab_atomic_{}.cnf", std::process::id()));
+
+        instance.to_file_atomic(&path).unwrap();
+        let read = Instance::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.get_clauses(), instance.get_clauses());
+
+        let missing_dir = std::env::temp_dir().join("sat_lab_missing_dir/instance.cnf");
+        assert!(instance.to_file_atomic(missing_dir).is_err());
+
+        let path =
+            std::env::temp_dir().join(format!("sat_lab_atomic_threads_{}.cnf", std::process::id()));
+        let instances: Vec<_> = (1..=8)
+            .map(|n| Instance::with_clauses(n, vec![Clause::from_cnf(vec![n as isize])]))
+            .collect();
+        std::thread::scope(|scope| {
+            for instance in &instances {
+                let path = &path;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        instance.to_file_atomic(path).unwrap();
+                    }
+                });
+            }
+        });
+        let read = Instance::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(instances
+            .iter()
+            .any(|instance| instance.get_clauses() == read.get_clauses()));
+    }
+
+    #[test]
+    fn qdimacs() {
+        let qdimacs = "p cnf 3 2\na 1 0\ne 2 3 0\n1 -2 0\n-3 2 0\n";
+        let instance = Instance::from_reader(qdimacs.as_bytes()).unwrap();
+
+        assert_eq!(
+            instance.quantifiers(),
+            &[
+                (Quantifier::Forall, vec![0]),
+                (Quantifier::Exists, vec![1, 2])
+            ]
+        );
+        assert_eq!(instance.get_clauses().len(), 2);
+
+        let mut out = Vec::new();
+        instance.to_writer(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), qdimacs);
+
+        let plain = Instance::from_reader("p cnf 1 1\n1 0\n".as_bytes()).unwrap();
+        assert!(plain.quantifiers().is_empty());
+
+        assert!(Instance::from_reader("p cnf 1 1\ne -1 0\n1 0\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn max_width() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![Clause::from_cnf(vec![1, -2, 3]), Clause::from_cnf(vec![3])],
+        );
+
+        assert_eq!(instance.max_width(), 3);
+        assert_eq!(Instance::with_clauses(3, vec![]).max_width(), 0);
+    }
+
+    #[test]
+    fn canonicalize() {
+        let mut a = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![3, -1]),
+                Clause::from_cnf(vec![2, -2]),
+                Clause::from_cnf(vec![2, 1, 2]),
+                Clause::from_cnf(vec![-1, 3]),
+            ],
+        );
+        let mut b = Instance::with_clauses(
+            3,
+            vec![Clause::from_cnf(vec![1, 2]), Clause::from_cnf(vec![3, -1])],
+        );
+        a.canonicalize();
+        b.canonicalize();
+
+        assert_eq!(
+            a.get_clauses(),
+            &vec![Clause::from_cnf(vec![1, 2]), Clause::from_cnf(vec![-1, 3])]
+        );
+
+        let (mut a_out, mut b_out) = (vec![], vec![]);
+        a.to_writer(&mut a_out).unwrap();
+        b.to_writer(&mut b_out).unwrap();
+        assert_eq!(a_out, b_out);
+    }
+
+    #[test]
+    fn sort_and_truncate_clauses() {
+        let mut instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2, 3]),
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![2, -3]),
+                Clause::from_cnf(vec![3]),
+            ],
+        );
+
+        instance.sort_clauses_by_score(|clause| -(clause.len() as f64));
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![3]),
+                Clause::from_cnf(vec![2, -3]),
+                Clause::from_cnf(vec![1, 2, 3]),
+            ]
+        );
+
+        instance.truncate_clauses(2);
+        assert_eq!(instance.get_clauses().len(), 4);
+
+        instance.add_learned_clause(Clause::from_cnf(vec![1, -3]));
+        instance.add_learned_clause(Clause::from_cnf(vec![-2]));
+        instance.add_clause(Clause::from_cnf(vec![1, 3]));
+        assert_eq!(instance.clause_kind(4), Some(ClauseKind::Learned));
+        assert_eq!(instance.clause_kind(6), Some(ClauseKind::Original));
+        assert_eq!(instance.clause_kind(7), None);
+
+        instance.sort_clauses_by_score(|clause| -(clause.len() as f64));
+        assert_eq!(instance.get_clauses()[2], Clause::from_cnf(vec![-2]));
+        assert_eq!(instance.clause_kind(2), Some(ClauseKind::Learned));
+
+        // The original clauses are kept past the first 2, the learned [1, -3] is removed but not [-2]
+        assert_eq!(instance.truncate_clauses(3), 1);
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![3]),
+                Clause::from_cnf(vec![-2]),
+                Clause::from_cnf(vec![2, -3]),
+                Clause::from_cnf(vec![1, 3]),
+                Clause::from_cnf(vec![1, 2, 3]),
+            ]
+        );
+        assert_eq!(instance.truncate_clauses(0), 1);
+        assert_eq!(instance.get_clauses().len(), 5);
+
+        let cnf = "p cnf 2 2\n1 2 0 c: long\n-1 0 c: short\n";
+        let options = ParseOptions {
+            clause_labels: true,
+            ..Default::default()
+        };
+        let mut labeled = Instance::from_reader_with(cnf.as_bytes(), &options).unwrap();
+        labeled.sort_clauses_by_score(|clause| -(clause.len() as f64));
+        assert_eq!(labeled.clause_label(0), Some("short"));
+        assert_eq!(labeled.clause_label(1), Some("long"));
+    }
+
+    #[test]
+    fn reserve() {
+        let mut instance = Instance::with_capacity(4, 10);
+        assert_eq!((instance.num_vars(), instance.get_clauses().len()), (4, 0));
+        assert!(instance.clauses.capacity() >= 10);
+
+        instance.add_clause(Clause::from_cnf(vec![1, 2]));
+        instance.reserve(100);
+        assert!(instance.clauses.capacity() >= 101);
+        assert_eq!(instance.get_clauses(), &vec![Clause::from_cnf(vec![1, 2])]);
+    }
+
+    #[test]
+    fn add_clause_dedup() {
+        let mut instance = Instance::with_clauses(3, vec![Clause::from_cnf(vec![1, -2])]);
+
+        assert!(!instance.add_clause_dedup(Clause::from_cnf(vec![-2, 1, 1])));
+        assert!(instance.add_clause_dedup(Clause::from_cnf(vec![2, 3])));
+        assert!(!instance.add_clause_dedup(Clause::from_cnf(vec![3, 2])));
+        assert_eq!(instance.get_clauses().len(), 2);
+
+        instance.remove_clause(1);
+        assert!(instance.add_clause_dedup(Clause::from_cnf(vec![3, 2])));
+        assert_eq!(
+            instance.get_clauses(),
+            &vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![3, 2])]
+        );
+    }
+
+    #[test]
+    fn num_literals() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, -2, 3]),
+                Clause::from_cnf(vec![2, 2]),
+                Clause::from_cnf(vec![-3]),
+            ],
+        );
+
+        assert_eq!(instance.num_literals(), 6);
+        assert_eq!(instance.avg_clause_len(), 2.0);
+        assert!(Instance::with_clauses(3, vec![]).avg_clause_len().is_nan());
+    }
+
+    #[test]
+    fn is_horn() {
+        let mut instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![-2, -3]),
+            ],
+        );
+        assert!(instance.is_horn());
+
+        instance.add_clause(Clause::from_cnf(vec![1, 3]));
+        assert!(!instance.is_horn());
+    }
+
+    #[test]
+    fn generation() {
+        let mut instance = Instance::with_clauses(2, vec![Clause::from_cnf(vec![1, -2])]);
+        let other = Instance::with_clauses(2, vec![Clause::from_cnf(vec![1, -2])]);
+        assert_ne!(instance.generation(), other.generation());
+        assert_eq!(instance.clone().generation(), instance.generation());
+
+        let last = instance.generation();
+        instance.add_clause(Clause::from_cnf(vec![2]));
+        assert_ne!(instance.generation(), last);
+        assert_eq!(instance.get_clauses().len(), 2);
+
+        let last = instance.generation();
+        assert_eq!(instance.assign(1, true), Some(()));
+        assert_ne!(instance.generation(), last);
+        assert_eq!(instance.count_sat(), 1);
+        assert_eq!(instance.assign(2, true), None);
+
+        let last = instance.generation();
+        assert_eq!(instance.remove_clause(0), Clause::from_cnf(vec![1, -2]));
+        assert_ne!(instance.generation(), last);
+        assert_eq!(instance.get_clauses(), &vec![Clause::from_cnf(vec![2])]);
+    }
+
+    #[test]
+    fn model() {
+        let mut instance = Instance::with_clauses(
+            2,
+            vec![Clause::from_cnf(vec![1]), Clause::from_cnf(vec![-2])],
+        );
+        assert_eq!(instance.model().into_vec(), vec![false, false]);
+        assert!(!instance.is_sat());
+
+        let generation = instance.generation();
+        instance.set_model(BoolVec::from(vec![true, false]));
+        assert_ne!(instance.generation(), generation);
+        assert_eq!(instance.model().into_vec(), vec![true, false]);
+        assert!(instance.is_sat());
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_model_panic() {
+        let mut instance = Instance::with_clauses(2, vec![]);
+        instance.set_model(boolvec![true; 3]);
+    }
+
+    #[test]
+    fn unsat_clauses() {
+        let instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![-2]),
+                Clause::from_cnf(vec![]),
+            ],
+        );
+
+        assert_eq!(
+            instance.unsat_clauses(&BoolVec::from([true, false])),
+            vec![1, 3]
+        );
+        assert_eq!(
+            instance.unsat_clauses(&BoolVec::from([false, false])),
+            vec![0, 3]
+        );
+    }
+
+    #[test]
+    fn shortest_unsat_clause() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2, 3]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![-1, 3]),
+                Clause::from_cnf(vec![-2, -3, 1]),
+            ],
+        );
+
+        assert_eq!(
+            instance.shortest_unsat_clause(&BoolVec::from([true, false, false])),
+            Some((1, &Clause::from_cnf(vec![-1, 2])))
+        );
+        assert_eq!(
+            instance.shortest_unsat_clause(&BoolVec::from([false, true, true])),
+            Some((3, &Clause::from_cnf(vec![-2, -3, 1])))
+        );
+        assert_eq!(
+            instance.shortest_unsat_clause(&BoolVec::from([true, true, true])),
+            None
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn novelty() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10 {
+            let mut instance = Instance::new_fuzz(12, 40, 3, &mut rng, false, false);
+            let sat = instance.solve().is_sat();
+
+            // Deterministic for the seed, the search is not guaranteed to find a model in general
+            assert_eq!(instance.novelty(2_000, 0.5, 0.01, &mut rng), sat);
+            assert_eq!(instance.is_sat(), sat);
+        }
+
+        let mut unsat = Instance::with_clauses(
+            1,
+            vec![Clause::from_cnf(vec![1]), Clause::from_cnf(vec![-1])],
+        );
+        assert!(!unsat.novelty(100, 0.5, 0.01, &mut rng));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    #[should_panic]
+    fn novelty_panic() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut instance = Instance::with_clauses(1, vec![Clause::from_cnf(vec![1])]);
+        instance.novelty(10, 1.5, 0.01, &mut StdRng::seed_from_u64(0));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn maximize() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1]),
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-2, 3]),
+                Clause::from_cnf(vec![-3, -1]),
+                Clause::from_cnf(vec![]),
+            ],
+        );
+
+        let (vars, score) = instance.maximize(100, &mut rng);
+        assert_eq!(score, 4);
+        assert_eq!(instance.count_sat(), 4);
+        assert_eq!(instance.vars, vars);
+
+        // Satisfied by the all-false assignment
+        let planted: Vec<_> = Instance::new_fuzz(20, 80, 3, &mut rng, false, false)
+            .get_clauses()
+            .iter()
+            .filter(|clause| clause.get_literals().iter().any(Literal::is_negated))
+            .cloned()
+            .collect();
+        let m = planted.len();
+        let mut satisfiable = Instance::with_clauses(20, planted);
+        assert_eq!(satisfiable.maximize(10_000, &mut rng).1, m);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn shuffle() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut instance = Instance::new_fuzz(6, 12, 3, &mut rng, true, false);
+        let mut original = instance.clone();
+        instance.shuffle(&mut rng);
+
+        assert_ne!(instance.get_clauses(), original.get_clauses());
+        assert_eq!(instance.count_models(), original.count_models());
+        for x in 0..64 {
+            let vars = BoolVec::from((0..6).map(|i| x & (1 << i) != 0).collect::<Vec<_>>());
+            instance.vars = BoolVec::from(vars.into_vec());
+            original.vars = vars;
+            assert_eq!(instance.is_sat(), original.is_sat());
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sample_seeded() {
+        let mut instance = Instance::with_clauses(64, vec![]);
+        let mut other = Instance::with_clauses(64, vec![]);
+
+        instance.sample_new_variables_seeded(42);
+        other.sample_new_variables_seeded(42);
+        assert_eq!(instance.vars, other.vars);
+
+        other.sample_new_variables_seeded(43);
+        assert_ne!(instance.vars, other.vars);
+    }
+
+    #[test]
+    fn is_sat() {
+        let mut instance = Instance::with_clauses(
+            2,
+            vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![2])],
+        );
+        assert!(!instance.is_sat());
+
+        instance.vars = BoolVec::from([true, true]);
+        assert!(instance.is_sat());
+        assert_eq!(instance.count_sat(), 2);
+
+        assert!(Instance::with_clauses(0, vec![]).is_sat());
+    }
+
+    #[test]
+    fn eval_as_dnf() {
+        // (x0 ∧ ¬x1) ∨ (x1 ∧ x2)
+        let instance = Instance::with_clauses(
+            3,
+            vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![2, 3])],
+        );
+
+        assert!(instance.eval_as_dnf(&BoolVec::from([true, false, false])));
+        assert!(instance.eval_as_dnf(&BoolVec::from([false, true, true])));
+        assert!(!instance.eval_as_dnf(&BoolVec::from([true, true, false])));
+
+        assert!(!Instance::with_clauses(1, vec![]).eval_as_dnf(&BoolVec::from([true])));
+        assert!(Instance::with_clauses(1, vec![Clause::from_cnf(vec![])])
+            .eval_as_dnf(&BoolVec::from([true])));
+    }
+
+    #[test]
+    fn used_vars() {
+        let instance = Instance::with_clauses(
+            5,
+            vec![Clause::from_cnf(vec![1, -4]), Clause::from_cnf(vec![-4, 2])],
+        );
+
+        assert_eq!(
+            instance.used_vars().into_vec(),
+            vec![true, true, false, true, false]
+        );
+    }
+
+    #[test]
+    fn python_literals() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![]),
+                Clause::from_cnf(vec![-3]),
+            ],
+        );
+
+        assert_eq!(
+            instance.to_python_literals(),
+            vec![vec![1, -2], vec![], vec![-3]]
+        );
+    }
+
+    #[test]
+    fn icnf() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![Clause::from_cnf(vec![1, -2]), Clause::from_cnf(vec![2, 3])],
+        );
+
+        let mut out = Vec::new();
+        instance
+            .to_icnf(
+                &[
+                    vec![Literal::from_cnf(1)],
+                    vec![Literal::from_cnf(-1), Literal::from_cnf(-3)],
+                ],
+                &mut out,
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "p inccnf\n1 -2 0\n2 3 0\na 1 0\na -1 -3 0\n"
+        );
+    }
 }
@@ -0,0 +1,78 @@
+use bool_vec::BoolVec;
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+#[cfg(feature = "rand")]
+use crate::instance::Instance;
+
+/// Returns the Hamming distance of the two assignments, the number of variables on which they differ.
+/// Panics if the assignments do not have the same length.
+pub fn hamming(a: &BoolVec, b: &BoolVec) -> usize {
+    assert_eq!(a.len(), b.len(), "assignments of different lengths");
+
+    (0..a.len()).filter(|&i| a.get(i) != b.get(i)).count()
+}
+
+#[cfg(feature = "rand")]
+impl Instance {
+    /// Returns the mean number of clauses satisfied by `samples` random assignments at Hamming distance exactly
+    /// `distance` from `base`, for fitness landscape studies.
+    /// Each sample flips `distance` distinct variables of `base` chosen uniformly, so the samples are uniform
+    /// over the assignments at that distance. Returns NaN if `samples` is 0.
+    /// Panics if `distance` exceeds the length of `base`.
+    pub fn sat_at_distance<R: Rng>(
+        &self,
+        base: &BoolVec,
+        distance: usize,
+        samples: usize,
+        rng: &mut R,
+    ) -> f64 {
+        let mut total = 0;
+        for _ in 0..samples {
+            let mut vars = BoolVec::from(base.into_vec());
+            for i in rand::seq::index::sample(rng, base.len(), distance) {
+                vars.set(i, !base.get(i).unwrap());
+            }
+            total += self
+                .clauses()
+                .filter(|clause| clause.test_sat(&vars))
+                .count();
+        }
+
+        total as f64 / samples as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hamming() {
+        let a = BoolVec::from([true, false, true, false]);
+        let b = BoolVec::from([true, true, false, false]);
+
+        assert_eq!(super::hamming(&a, &b), 2);
+        assert_eq!(super::hamming(&a, &a), 0);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sat_at_distance() {
+        use crate::clause::Clause;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let instance =
+            Instance::with_clauses(4, (1..=4).map(|var| Clause::from_cnf(vec![var])).collect());
+        let base = BoolVec::from([true; 4]);
+
+        // Each flip falsifies exactly one unit clause
+        for distance in 0..=4 {
+            let mean = instance.sat_at_distance(&base, distance, 20, &mut rng);
+            assert_eq!(mean, (4 - distance) as f64);
+        }
+        assert!(instance.sat_at_distance(&base, 1, 0, &mut rng).is_nan());
+    }
+}
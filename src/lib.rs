@@ -1,7 +1,42 @@
+pub mod activity;
+pub mod assignment;
+pub mod batch;
+mod binary;
 pub mod clause;
+pub mod compact;
+pub mod dense;
+#[cfg(feature = "external_solver")]
+mod external;
 pub mod instance;
+pub mod landscape;
 pub mod literal;
+pub mod occurrence;
+pub mod parse;
+pub mod proof;
+pub mod quantifier;
+pub mod solver;
+pub mod stats;
+pub mod truth_table;
+mod union_find;
+pub mod var_map;
+pub mod weighted;
+pub mod write;
 
-pub use clause::Clause;
+pub use activity::Activity;
+pub use assignment::Assignment;
+pub use clause::{Clause, ClauseKind, ClauseValue};
+pub use compact::CompactClauses;
+pub use dense::DenseInstance;
 pub use instance::Instance;
-pub use literal::Literal;
+pub use landscape::hamming;
+pub use literal::{Literal, LiteralBase, LiteralRepr};
+pub use occurrence::{OccurrenceIndex, VarPolarity};
+pub use parse::{CnfParseError, ParseOptions, ParseWarning};
+pub use proof::ProofError;
+pub use quantifier::Quantifier;
+pub use solver::{SolveResult, SolveStats, SubSolver};
+pub use stats::{InstanceStats, StatsAccumulator};
+pub use truth_table::TruthTable;
+pub use var_map::VarMap;
+pub use weighted::WeightedInstance;
+pub use write::WriteOptions;
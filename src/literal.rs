@@ -1,35 +1,59 @@
 use bool_vec::BoolVec;
 
+/// The integer storing a literal: `isize` by default, `i32` with the `small-literals` feature,
+/// which halves the memory of the clauses for instances of less than `i32::MAX` variables.
+#[cfg(not(feature = "small-literals"))]
+pub type LiteralRepr = isize;
+
+/// The integer storing a literal: `isize` by default, `i32` with the `small-literals` feature,
+/// which halves the memory of the clauses for instances of less than `i32::MAX` variables.
+#[cfg(feature = "small-literals")]
+pub type LiteralRepr = i32;
+
 /// Stores a literal from a SAT instance problem.
-/// A Literal is a Variable reference that may be negated, stored in a single isize for efficient storage.
+/// A Literal is a Variable reference that may be negated, stored in a single `LiteralRepr` for efficient storage.
 ///
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub struct Literal(isize);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Literal(LiteralRepr);
 
 impl Literal {
+    /// The largest variable index a literal can refer to.
+    pub const MAX_INDEX: usize = LiteralRepr::MAX as usize - 1;
+
     /// Creates a new literal from a variable index and a negation flag.
+    /// Panics if `var_index` is greater than `MAX_INDEX`.
     pub fn new(var_index: usize, negated: bool) -> Self {
-        assert!(var_index < isize::MAX as usize);
+        assert!(var_index <= Self::MAX_INDEX);
 
-        let var_index = var_index as isize + 1;
+        let var_index = var_index as LiteralRepr + 1;
 
         Self(if negated { -var_index } else { var_index })
     }
 
     /// Creates a new literal from a CNF representation.
+    /// Panics if `cnf` does not fit in a `LiteralRepr`.
     pub fn from_cnf(cnf: isize) -> Self {
-        Self(cnf)
+        Self(LiteralRepr::try_from(cnf).expect("CNF literal out of range"))
+    }
+
+    /// Creates a new literal from a 0-based CNF representation, where `v` is the variable index `v`
+    /// and `-v` its negation.
+    /// As `-0` cannot be told apart from `0`, the negation of the variable 0 must be built with `new`.
+    pub fn from_cnf_0based(cnf: isize) -> Self {
+        Self::new(cnf.unsigned_abs(), cnf.is_negative())
     }
 
     /// Returns the CNF representation of the literal.
+    #[allow(clippy::unnecessary_cast)] // `LiteralRepr` may not be `isize`
     pub fn as_cnf(&self) -> isize {
-        self.0
+        self.0 as isize
     }
 
     /// Returns the variable index of the literal.
+    #[allow(clippy::unnecessary_cast)] // `LiteralRepr` may not be `isize`
     pub fn index(&self) -> usize {
-        self.0.abs() as usize - 1
+        self.0.unsigned_abs() as usize - 1
     }
 
     /// Returns whether the literal is negated.
@@ -39,7 +63,7 @@ impl Literal {
 
     /// Returns the negated literal.
     pub fn negated(&self) -> Literal {
-        Self::from_cnf(-self.0)
+        Self(-self.0)
     }
 
     /// Negates the literal in place.
@@ -48,6 +72,17 @@ impl Literal {
         self
     }
 
+    /// Returns whether the literal is over the variable `var`, in either polarity.
+    pub fn is_var(&self, var: usize) -> bool {
+        self.index() == var
+    }
+
+    /// Compares the polarity of two literals over the same variable: `Some(true)` if they are equal,
+    /// `Some(false)` if they are opposite (a possible resolution pivot), `None` if they are over different variables.
+    pub fn agrees_with(&self, other: Literal) -> Option<bool> {
+        (self.index() == other.index()).then(|| *self == other)
+    }
+
     /// Evaluates the literal with the given variable values (that is, possibly negated).
     /// Panics if the literal is not present in the given variables.
     /// Use `try_eval_with` for the faillible version.
@@ -55,6 +90,14 @@ impl Literal {
         self.try_eval_with(vars).unwrap()
     }
 
+    /// Evaluates the literal with variable values packed in 64-bit words: the value of the variable `i`
+    /// is the bit `i % 64` (counted from the least significant bit) of the word `i / 64`.
+    /// Panics if the literal is not present in the given words.
+    pub fn eval_bits(&self, bits: &[u64]) -> bool {
+        let index = self.index();
+        (bits[index / 64] >> (index % 64) & 1 != 0) ^ self.is_negated()
+    }
+
     /// Evaluates the literal with the given variable values (that is, possibly negated).
     /// Returns `None` if the literal is not present in the given variables.
     pub fn try_eval_with(&self, vars: &BoolVec) -> Option<bool> {
@@ -62,7 +105,44 @@ impl Literal {
     }
 }
 
+/// The numbering of the variables in a CNF file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LiteralBase {
+    /// Standard DIMACS numbering: the variable `1` is the index 0 and clauses are terminated by `0`.
+    #[default]
+    One,
+
+    /// The variable `0` is the index 0, and its negation is written `-0`.
+    /// Since `0` is then a valid literal, clauses are terminated by the `terminator` sentinel instead,
+    /// which must not be a valid literal of the file (e.g. `isize::MIN`).
+    Zero { terminator: isize },
+}
+
+/// `-lit` is the negated literal, same as `lit.negated()`.
+impl std::ops::Neg for Literal {
+    type Output = Literal;
+
+    fn neg(self) -> Self::Output {
+        self.negated()
+    }
+}
+
+impl PartialOrd for Literal {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Literals are ordered by variable index, the positive literal before the negated one.
+impl Ord for Literal {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.0.unsigned_abs(), self.0.is_negative())
+            .cmp(&(other.0.unsigned_abs(), other.0.is_negative()))
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -88,18 +168,90 @@ mod tests {
         assert_eq!(neg.is_negated(), true);
     }
 
+    #[test]
+    fn cnf_0based() {
+        let first = Literal::from_cnf_0based(0);
+        let neg = Literal::from_cnf_0based(-2);
+
+        assert_eq!(first, Literal::from_cnf(1));
+        assert_eq!(neg, Literal::from_cnf(-3));
+    }
+
+    #[cfg(not(feature = "small-literals"))]
+    #[test]
+    fn repr() {
+        assert_eq!(Literal::MAX_INDEX, (isize::MAX - 1) as usize);
+        assert_eq!(std::mem::size_of::<Literal>(), std::mem::size_of::<isize>());
+    }
+
+    #[cfg(feature = "small-literals")]
+    #[test]
+    fn repr() {
+        assert_eq!(Literal::MAX_INDEX, (i32::MAX - 1) as usize);
+        assert_eq!(std::mem::size_of::<Literal>(), 4);
+    }
+
+    #[cfg(feature = "small-literals")]
+    #[test]
+    #[should_panic]
+    fn from_cnf_panic() {
+        Literal::from_cnf(i32::MAX as isize + 1);
+    }
+
+    #[test]
+    fn neg() {
+        let lit = Literal::new(2, false);
+
+        assert_eq!(-lit, lit.negated());
+        assert_eq!(-(-lit), lit);
+    }
+
+    #[test]
+    fn same_var() {
+        let lit = Literal::from_cnf(-3);
+
+        assert!(lit.is_var(2));
+        assert!(!lit.is_var(3));
+        assert_eq!(lit.agrees_with(Literal::from_cnf(-3)), Some(true));
+        assert_eq!(lit.agrees_with(Literal::from_cnf(3)), Some(false));
+        assert_eq!(lit.agrees_with(Literal::from_cnf(-2)), None);
+    }
+
+    #[test]
+    fn eval_bits() {
+        let bits = [0b101, 1 << 2];
+
+        assert!(Literal::from_cnf(1).eval_bits(&bits));
+        assert!(!Literal::from_cnf(2).eval_bits(&bits));
+        assert!(Literal::from_cnf(-2).eval_bits(&bits));
+        assert!(!Literal::from_cnf(-3).eval_bits(&bits));
+        assert!(Literal::from_cnf(67).eval_bits(&bits));
+        assert!(!Literal::from_cnf(66).eval_bits(&bits));
+    }
+
+    #[test]
+    fn ordering() {
+        let mut literals: Vec<_> = [3, -1, 2, 1, -3].map(Literal::from_cnf).into();
+        literals.sort();
+
+        assert_eq!(literals, [1, -1, 2, 3, -3].map(Literal::from_cnf));
+
+        assert!(Literal::default() < Literal::from_cnf(1));
+    }
+
     #[test]
     #[should_panic]
     fn new_panic() {
-        Literal::new(isize::MAX as usize, false);
+        Literal::new(Literal::MAX_INDEX + 1, false);
     }
 
     #[test]
     fn new_max_index() {
-        let non_neg = Literal::new((isize::MAX - 1) as usize, false);
-        let neg = Literal::new((isize::MAX - 1) as usize, true);
+        let non_neg = Literal::new(Literal::MAX_INDEX, false);
+        let neg = Literal::new(Literal::MAX_INDEX, true);
 
-        assert_eq!(non_neg.index(), (isize::MAX - 1) as usize);
-        assert_eq!(neg.index(), (isize::MAX - 1) as usize);
+        assert_eq!(non_neg.index(), Literal::MAX_INDEX);
+        assert_eq!(neg.index(), Literal::MAX_INDEX);
+        assert_eq!(non_neg.negated(), neg);
     }
 }
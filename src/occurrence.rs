@@ -0,0 +1,168 @@
+use crate::{instance::Instance, literal::Literal};
+
+/// The polarities in which a variable appears in the clauses, see `Instance::polarity_profile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarPolarity {
+    /// The variable only appears positively, it is a pure literal.
+    PositiveOnly,
+    /// The variable only appears negated, it is a pure literal.
+    NegativeOnly,
+    /// The variable appears in both polarities.
+    Mixed,
+    /// The variable appears in no clause.
+    Absent,
+}
+
+/// An index of the clauses containing each literal, split by polarity.
+/// The index is a snapshot: it records the generation of the instance it was built from,
+/// and `is_valid` tells whether the instance has been mutated since.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OccurrenceIndex {
+    generation: u64,
+    /// Indices of the clauses containing the variable `i` positively.
+    positive: Vec<Vec<usize>>,
+    /// Indices of the clauses containing the variable `i` negated.
+    negative: Vec<Vec<usize>>,
+}
+
+impl OccurrenceIndex {
+    /// Builds the index of the clauses of the instance.
+    /// Literals over variables not present in the instance are indexed as well.
+    pub fn new(instance: &Instance) -> Self {
+        let n = instance
+            .clauses()
+            .flat_map(|clause| clause.get_literals())
+            .map(|lit| lit.index() + 1)
+            .max()
+            .unwrap_or(0)
+            .max(instance.num_vars());
+
+        let mut positive = vec![vec![]; n];
+        let mut negative = vec![vec![]; n];
+        for (i, clause) in instance.clauses().enumerate() {
+            for lit in clause.get_literals() {
+                let occurrences = if lit.is_negated() {
+                    &mut negative[lit.index()]
+                } else {
+                    &mut positive[lit.index()]
+                };
+
+                // A literal repeated in a clause is indexed once
+                if occurrences.last() != Some(&i) {
+                    occurrences.push(i);
+                }
+            }
+        }
+
+        Self {
+            generation: instance.generation(),
+            positive,
+            negative,
+        }
+    }
+
+    /// Returns whether the index is up to date with the instance, that is whether it was built from
+    /// this instance (or one of its clones) and the instance has not been mutated since.
+    pub fn is_valid(&self, instance: &Instance) -> bool {
+        self.generation == instance.generation()
+    }
+
+    /// Returns the indices of the clauses containing `lit`, in increasing order.
+    pub fn occurrences(&self, lit: Literal) -> &[usize] {
+        let occurrences = if lit.is_negated() {
+            &self.negative
+        } else {
+            &self.positive
+        };
+
+        occurrences.get(lit.index()).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the polarities in which the variable `var` appears in the indexed clauses.
+    pub fn polarity(&self, var: usize) -> VarPolarity {
+        let positive = !self.occurrences(Literal::new(var, false)).is_empty();
+        let negative = !self.occurrences(Literal::new(var, true)).is_empty();
+
+        match (positive, negative) {
+            (true, false) => VarPolarity::PositiveOnly,
+            (false, true) => VarPolarity::NegativeOnly,
+            (true, true) => VarPolarity::Mixed,
+            (false, false) => VarPolarity::Absent,
+        }
+    }
+
+    /// Returns the indices of the clauses containing the variable `var` in either polarity, in increasing order.
+    pub fn var_occurrences(&self, var: usize) -> Vec<usize> {
+        let mut occurrences = [
+            self.occurrences(Literal::new(var, false)),
+            self.occurrences(Literal::new(var, true)),
+        ]
+        .concat();
+        occurrences.sort_unstable();
+        occurrences.dedup();
+
+        occurrences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clause::Clause;
+
+    #[test]
+    fn occurrences() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![2, 2, 1]),
+                Clause::from_cnf(vec![-1, -2]),
+            ],
+        );
+        let index = instance.occurrence_index();
+
+        assert_eq!(index.occurrences(Literal::from_cnf(1)), &[0, 1]);
+        assert_eq!(index.occurrences(Literal::from_cnf(-1)), &[2]);
+        assert_eq!(index.occurrences(Literal::from_cnf(2)), &[1]);
+        assert_eq!(index.occurrences(Literal::from_cnf(-2)), &[0, 2]);
+        assert!(index.occurrences(Literal::from_cnf(3)).is_empty());
+        assert!(index.occurrences(Literal::from_cnf(-7)).is_empty());
+        assert_eq!(index.var_occurrences(1), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn polarity() {
+        let instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![-2, 3]),
+                Clause::from_cnf(vec![-3]),
+            ],
+        );
+
+        assert_eq!(
+            instance.polarity_profile(),
+            vec![
+                VarPolarity::PositiveOnly,
+                VarPolarity::NegativeOnly,
+                VarPolarity::Mixed,
+                VarPolarity::Absent
+            ]
+        );
+    }
+
+    #[test]
+    fn validity() {
+        let mut instance = Instance::with_clauses(2, vec![Clause::from_cnf(vec![1])]);
+        let index = instance.occurrence_index();
+        assert!(index.is_valid(&instance));
+        assert!(index.is_valid(&instance.clone()));
+        assert!(!index.is_valid(&Instance::with_clauses(2, vec![Clause::from_cnf(vec![1])])));
+
+        instance.add_clause(Clause::from_cnf(vec![-2]));
+        assert!(!index.is_valid(&instance));
+        assert!(instance.occurrence_index().is_valid(&instance));
+    }
+}
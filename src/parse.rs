@@ -0,0 +1,269 @@
+use std::{borrow::Cow, error::Error, fmt, io};
+
+use crate::{
+    clause::Clause,
+    literal::{Literal, LiteralBase, LiteralRepr},
+    quantifier::Quantifier,
+};
+
+/// Options of the CNF parser, to read the near-DIMACS dialects found in benchmark collections.
+/// The default options parse standard DIMACS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// The first character of the comment lines preceding the header.
+    pub comment_char: char,
+
+    /// Whether each clause must be terminated (by `0` in DIMACS).
+    /// A clause spans the lines up to its terminator, as written by `WriteOptions::max_line_width`,
+    /// so when false only the last clause may omit it, ended by the end of the content.
+    pub require_terminator: bool,
+
+    /// The problem type expected in the header, `p <problem_type> n m`.
+    pub problem_type: String,
+
+    /// The numbering of the variables.
+    pub base: LiteralBase,
+
+    /// Whether clauses may refer to more variables than declared in the header, for buggy generators
+    /// lagging behind their output. The instance then has as many variables as referred to,
+    /// which `Instance::from_reader_with_warnings` reports as a `ParseWarning::VarCount`.
+    /// When false, such a clause is an error.
+    pub grow_vars: bool,
+
+    /// Whether to keep the trailing annotation `c: label` of the clause lines as the clause label,
+    /// see `Instance::clause_label`. When false, such annotations are ignored.
+    pub clause_labels: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            comment_char: 'c',
+            require_terminator: false,
+            problem_type: "cnf".to_string(),
+            base: LiteralBase::One,
+            grow_vars: false,
+            clause_labels: false,
+        }
+    }
+}
+
+/// A defect repaired by the lossy parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// The clause starting at the given line (1-based) was not terminated, the end of the content was taken as its terminator.
+    MissingTerminator { line: usize },
+
+    /// The blank line at the given line (1-based) was ignored.
+    BlankLine { line: usize },
+
+    /// The header declared `declared` clauses but `found` were read, the instance keeps all of them.
+    ClauseCount { declared: usize, found: usize },
+
+    /// The header declared `declared` variables but the clauses refer to `found`, the instance has `found` variables.
+    VarCount { declared: usize, found: usize },
+}
+
+/// An error of the CNF parser.
+#[derive(Debug)]
+pub enum CnfParseError {
+    /// The content could not be read.
+    Io(io::Error),
+
+    /// The header line `p cnf n m` is missing or malformed.
+    Header,
+
+    /// The quantifier line at the given line (1-based) is malformed.
+    Quantifier { line: usize },
+
+    /// The clause at the given line (1-based) is malformed.
+    Clause { line: usize },
+
+    /// The clause or quantifier at the given line (1-based) refers to a variable not declared in the header.
+    UndeclaredVar { line: usize },
+}
+
+impl fmt::Display for CnfParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{error}"),
+            Self::Header => write!(f, "missing or malformed header"),
+            Self::Quantifier { line } => write!(f, "malformed quantifier at line {line}"),
+            Self::Clause { line } => write!(f, "malformed clause at line {line}"),
+            Self::UndeclaredVar { line } => write!(f, "undeclared variable at line {line}"),
+        }
+    }
+}
+
+impl Error for CnfParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CnfParseError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<CnfParseError> for io::Error {
+    fn from(error: CnfParseError) -> Self {
+        match error {
+            CnfParseError::Io(error) => error,
+            error => io::Error::new(io::ErrorKind::InvalidInput, error),
+        }
+    }
+}
+
+/// Returns the number of variables the clauses refer to, that is the largest variable index plus one.
+pub(crate) fn referred_vars(clauses: &[Clause]) -> usize {
+    clauses
+        .iter()
+        .flat_map(Clause::get_literals)
+        .map(|lit| lit.index() + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Parses the header line `p <problem_type> n m` following the comment lines, and returns `(n, m)`.
+/// Returns `None` if the header is missing or malformed.
+pub(crate) fn parse_header<'a, I>(lines: &mut I, options: &ParseOptions) -> Option<(usize, usize)>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut param_line = lines
+        .find(|x| !x.starts_with(options.comment_char))?
+        .split_whitespace()
+        .skip(1);
+
+    let problem_type = param_line.next()?;
+    if problem_type != options.problem_type {
+        return None;
+    }
+
+    let n = param_line.next()?.parse().ok()?;
+    let m = param_line.next()?.parse().ok()?;
+
+    Some((n, m))
+}
+
+/// Parses a QDIMACS quantifier line, `e 1 2 0` or `a 3 0`, into the quantifier and its variable indices.
+/// Returns `None` if the line is not a quantifier line or is malformed.
+pub(crate) fn parse_quantifier(
+    line: &str,
+    options: &ParseOptions,
+) -> Option<(Quantifier, Vec<usize>)> {
+    let mut chars = line.chars();
+    let quantifier = Quantifier::from_char(chars.next()?)?;
+
+    let clause = parse_clause(chars.as_str(), options)?;
+    if clause.get_literals().iter().any(Literal::is_negated) {
+        return None;
+    }
+
+    Some((
+        quantifier,
+        clause.get_literals().iter().map(Literal::index).collect(),
+    ))
+}
+
+/// Parses the literals of a clause line up to its terminator.
+/// Returns `None` if a literal is malformed, or if the terminator is missing while required.
+pub(crate) fn parse_clause(line: &str, options: &ParseOptions) -> Option<Clause> {
+    let (clause, terminated) = parse_clause_tokens(line, options.base)?;
+
+    if options.require_terminator && !terminated {
+        return None;
+    }
+
+    Some(clause)
+}
+
+/// Parses a clause line and, with `options.clause_labels`, the label of its trailing annotation `c: label`
+/// (with the comment character of the options).
+/// Returns `None` if the clause is malformed.
+pub(crate) fn parse_labeled_clause(
+    line: &str,
+    options: &ParseOptions,
+) -> Option<(Clause, Option<String>)> {
+    let (clause, label) = split_label(line, options);
+    Some((
+        parse_clause(clause, options)?,
+        label.map(|label| label.trim().to_string()),
+    ))
+}
+
+/// Splits a clause line at its trailing annotation `c: label` with `options.clause_labels`.
+fn split_label<'a>(line: &'a str, options: &ParseOptions) -> (&'a str, Option<&'a str>) {
+    let marker = format!("{}:", options.comment_char);
+    match line.split_once(&marker) {
+        Some((clause, label)) if options.clause_labels => (clause, Some(label)),
+        _ => (line, None),
+    }
+}
+
+/// Returns whether the clause line holds the terminator, or is malformed.
+pub(crate) fn ends_clause(line: &str, options: &ParseOptions) -> bool {
+    parse_clause_tokens(split_label(line, options).0, options.base)
+        .is_none_or(|(_, terminated)| terminated)
+}
+
+/// Groups the numbered clause lines by clause, each clause spanning the lines up to the one holding its terminator,
+/// joined by a space, and numbered by its first line.
+/// A malformed line ends its clause, for the parser to report it.
+pub(crate) fn clause_lines<'a, I>(
+    mut lines: I,
+    options: &'a ParseOptions,
+) -> impl Iterator<Item = (usize, Cow<'a, str>)> + 'a
+where
+    I: Iterator<Item = (usize, &'a str)> + 'a,
+{
+    std::iter::from_fn(move || {
+        let (i, first) = lines.next()?;
+        if ends_clause(first, options) {
+            return Some((i, Cow::Borrowed(first)));
+        }
+
+        let mut clause = first.to_string();
+        for (_, line) in lines.by_ref() {
+            clause.push(' ');
+            clause.push_str(line);
+            if ends_clause(line, options) {
+                break;
+            }
+        }
+        Some((i, Cow::Owned(clause)))
+    })
+}
+
+/// Parses the literals of a clause line up to its terminator, and returns whether the terminator was found.
+/// Returns `None` if a literal is malformed.
+pub(crate) fn parse_clause_tokens(line: &str, base: LiteralBase) -> Option<(Clause, bool)> {
+    let mut literals = vec![];
+    let mut terminated = false;
+
+    for token in line.split_whitespace() {
+        let value: isize = token.parse().ok()?;
+        literals.push(match base {
+            LiteralBase::One if value == 0 => {
+                terminated = true;
+                break;
+            }
+            LiteralBase::One if LiteralRepr::try_from(value).is_err() => return None,
+            LiteralBase::One => Literal::from_cnf(value),
+            LiteralBase::Zero { terminator } if value == terminator => {
+                terminated = true;
+                break;
+            }
+            // `-0` parses as 0, so the polarity is read from the token itself
+            LiteralBase::Zero { .. } if value.unsigned_abs() > Literal::MAX_INDEX => return None,
+            LiteralBase::Zero { .. } => Literal::new(value.unsigned_abs(), token.starts_with('-')),
+        });
+    }
+
+    Some((literals.into_iter().collect(), terminated))
+}
@@ -0,0 +1,138 @@
+use std::{error::Error, fmt};
+
+use crate::{clause::Clause, instance::Instance};
+
+/// An error of `Instance::check_resolution_proof`, the steps being numbered from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    /// The step refers to a clause index that is neither an original clause nor derived by a previous step.
+    MissingClause { step: usize, index: usize },
+
+    /// The two clauses of the step do not clash on its pivot, one must contain it positively and the other negated.
+    InvalidPivot { step: usize },
+
+    /// The last step does not derive the empty clause, or there is no step.
+    NotRefutation,
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingClause { step, index } => {
+                write!(f, "step {step} refers to the missing clause {index}")
+            }
+            Self::InvalidPivot { step } => write!(f, "invalid pivot at step {step}"),
+            Self::NotRefutation => write!(f, "the proof does not derive the empty clause"),
+        }
+    }
+}
+
+impl Error for ProofError {}
+
+impl Instance {
+    /// Checks a resolution refutation of the instance, given as steps `(a, b, pivot)` each resolving the clauses
+    /// `a` and `b` on the variable `pivot` (see `Clause::resolve`).
+    /// The clauses are indexed in a growing list: the original clauses first, then the resolvent of each step
+    /// in order, so that the resolvent of step `i` has the index `m + i` for `m` original clauses.
+    /// Returns `Ok` if every step is valid and the last one derives the empty clause, proving the instance unsatisfiable.
+    pub fn check_resolution_proof(
+        &self,
+        steps: &[(usize, usize, usize)],
+    ) -> Result<(), ProofError> {
+        let original = self.get_clauses();
+        let mut derived: Vec<Clause> = Vec::with_capacity(steps.len());
+
+        for (step, &(a, b, pivot)) in steps.iter().enumerate() {
+            let get = |index: usize| match index.checked_sub(original.len()) {
+                None => Ok(&original[index]),
+                Some(i) => derived
+                    .get(i)
+                    .ok_or(ProofError::MissingClause { step, index }),
+            };
+
+            let resolvent = get(a)?
+                .resolve(get(b)?, pivot)
+                .ok_or(ProofError::InvalidPivot { step })?;
+            derived.push(resolvent);
+        }
+
+        match derived.last() {
+            Some(clause) if clause.is_empty() => Ok(()),
+            _ => Err(ProofError::NotRefutation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_resolution_proof() {
+        let instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![1, -2]),
+                Clause::from_cnf(vec![-1, -2]),
+            ],
+        );
+
+        // [2] from 0 and 1, [-2] from 2 and 3, then the empty clause
+        assert_eq!(
+            instance.check_resolution_proof(&[(0, 1, 0), (2, 3, 0), (4, 5, 1)]),
+            Ok(())
+        );
+        assert_eq!(
+            instance.check_resolution_proof(&[(0, 1, 0), (2, 3, 0)]),
+            Err(ProofError::NotRefutation)
+        );
+        assert_eq!(
+            instance.check_resolution_proof(&[]),
+            Err(ProofError::NotRefutation)
+        );
+        assert_eq!(
+            instance.check_resolution_proof(&[(0, 1, 0), (4, 5, 1)]),
+            Err(ProofError::MissingClause { step: 1, index: 5 })
+        );
+        assert_eq!(
+            instance.check_resolution_proof(&[(0, 2, 0)]),
+            Err(ProofError::InvalidPivot { step: 0 })
+        );
+    }
+
+    #[test]
+    fn satisfiable_not_certified() {
+        let tautology = Instance::with_clauses(1, vec![Clause::from_cnf(vec![1, -1])]);
+        assert_eq!(
+            tautology.check_resolution_proof(&[(0, 0, 0)]),
+            Err(ProofError::NotRefutation)
+        );
+
+        let instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, -1, 2]),
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![-2]),
+            ],
+        );
+        assert!(instance.solve().is_sat());
+        assert_eq!(
+            instance.check_resolution_proof(&[(0, 1, 0), (3, 2, 1)]),
+            Err(ProofError::NotRefutation)
+        );
+
+        // No proof of up to two steps is accepted
+        let steps: Vec<_> = (0..4)
+            .flat_map(|a| (0..4).flat_map(move |b| (0..2).map(move |pivot| (a, b, pivot))))
+            .collect();
+        for &first in &steps {
+            assert!(instance.check_resolution_proof(&[first]).is_err());
+            for &second in &steps {
+                assert!(instance.check_resolution_proof(&[first, second]).is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt;
+
+/// A quantifier of the QBF prefix, as in QDIMACS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quantifier {
+    /// Existential quantifier, `e` in QDIMACS.
+    Exists,
+    /// Universal quantifier, `a` in QDIMACS.
+    Forall,
+}
+
+impl Quantifier {
+    /// Returns the quantifier of the QDIMACS prefix character, `e` or `a`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'e' => Some(Self::Exists),
+            'a' => Some(Self::Forall),
+            _ => None,
+        }
+    }
+
+    /// Returns the QDIMACS prefix character of the quantifier.
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Exists => 'e',
+            Self::Forall => 'a',
+        }
+    }
+}
+
+impl fmt::Display for Quantifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Exists => '∃',
+                Self::Forall => '∀',
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chars() {
+        for quantifier in [Quantifier::Exists, Quantifier::Forall] {
+            assert_eq!(
+                Quantifier::from_char(quantifier.as_char()),
+                Some(quantifier)
+            );
+        }
+        assert_eq!(Quantifier::from_char('c'), None);
+    }
+}
@@ -0,0 +1,1066 @@
+use crate::{
+    assignment::Assignment, clause::Clause, instance::Instance, literal::Literal,
+    union_find::UnionFind,
+};
+
+use bool_vec::{boolvec, BoolVec};
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+use std::{
+    io::{self, Write},
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+/// The outcome of solving an instance.
+#[derive(Debug, PartialEq)]
+pub enum SolveResult {
+    /// The instance is satisfiable, with the given model.
+    Sat(BoolVec),
+    /// The instance is unsatisfiable.
+    Unsat,
+    /// The solver could not decide.
+    Unknown,
+}
+
+impl SolveResult {
+    /// Returns true if the instance was found satisfiable.
+    pub fn is_sat(&self) -> bool {
+        matches!(self, Self::Sat(_))
+    }
+
+    /// Returns true if the instance was proven unsatisfiable.
+    pub fn is_unsat(&self) -> bool {
+        matches!(self, Self::Unsat)
+    }
+
+    /// Returns the model if the instance was found satisfiable.
+    pub fn model(&self) -> Option<&BoolVec> {
+        match self {
+            Self::Sat(model) => Some(model),
+            _ => None,
+        }
+    }
+
+    /// Writes the result following the SAT competition conventions: the line `s SATISFIABLE`,
+    /// `s UNSATISFIABLE` or `s UNKNOWN`, followed for a model by the line `v 1 -2 3 ... 0`
+    /// giving the value of each of the first `num_vars` variables.
+    /// Panics if the model has fewer than `num_vars` variables.
+    pub fn to_competition_output<W: Write>(&self, w: &mut W, num_vars: usize) -> io::Result<()> {
+        match self {
+            Self::Sat(model) => {
+                writeln!(w, "s SATISFIABLE")?;
+                write!(w, "v")?;
+                for i in 0..num_vars {
+                    let lit =
+                        Literal::new(i, !model.get(i).expect("variable missing from the model"));
+                    write!(w, " {}", lit.as_cnf())?;
+                }
+                writeln!(w, " 0")
+            }
+            Self::Unsat => writeln!(w, "s UNSATISFIABLE"),
+            Self::Unknown => writeln!(w, "s UNKNOWN"),
+        }
+    }
+}
+
+/// Counters of a DPLL search, see `Instance::solve_instrumented`.
+/// Unlike the duration, the counters only depend on the instance and the solver, not on the machine.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// The number of branches tried, that is the values given to a branching variable,
+    /// the second value after backtracking included.
+    pub decisions: u64,
+    /// The number of conflicts, that is the clauses found with all their literals false, each causing a backtrack.
+    pub conflicts: u64,
+    /// The number of variables assigned by unit propagation.
+    pub propagations: u64,
+    /// The wall-clock time of the search.
+    pub duration: Duration,
+}
+
+/// A polynomial-time solver deciding a fragment of SAT, see `SubSolver::decide`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubSolver {
+    /// Unit propagation, deciding the instances it refutes or satisfies without branching.
+    UnitProp,
+    /// The SCC algorithm on the implication graph, deciding the instances with at most two literals per clause.
+    TwoSat,
+    /// Unit propagation then setting the other variables to false, deciding the Horn instances.
+    Horn,
+}
+
+impl SubSolver {
+    /// Returns whether the instance is satisfiable if it is in the fragment decided by the solver, `None` otherwise.
+    pub fn decide(&self, instance: &Instance) -> Option<bool> {
+        let solver = Dpll {
+            clauses: instance.get_clauses(),
+        };
+        let mut values = vec![None; instance.num_vars()];
+
+        match self {
+            Self::UnitProp => {
+                if !solver.propagate(&mut values) {
+                    Some(false)
+                } else {
+                    solver.pick_branch(&values).is_none().then_some(true)
+                }
+            }
+            Self::TwoSat => instance
+                .clauses()
+                .all(|clause| clause.len() <= 2)
+                .then(|| two_sat(instance)),
+            // Once propagated, an unsatisfied Horn clause has two unassigned literals, one of them negated,
+            // so setting the unassigned variables to false satisfies it
+            Self::Horn => instance.is_horn().then(|| solver.propagate(&mut values)),
+        }
+    }
+}
+
+/// Decides an instance of clauses of at most two literals: it is unsatisfiable if and only if
+/// a variable and its negation are in the same strongly connected component of the implication graph.
+fn two_sat(instance: &Instance) -> bool {
+    let node = |lit: Literal| 2 * lit.index() + lit.is_negated() as usize;
+    let num_nodes = 2 * instance.num_vars();
+
+    let mut graph = vec![vec![]; num_nodes];
+    let mut reversed = vec![vec![]; num_nodes];
+    for clause in instance.clauses() {
+        let (a, b) = match clause.get_literals() {
+            [] => return false,
+            &[a] => (a, a),
+            &[a, b] => (a, b),
+            _ => unreachable!("clauses have at most two literals"),
+        };
+        for (from, to) in [(-a, b), (-b, a)] {
+            graph[node(from)].push(node(to));
+            reversed[node(to)].push(node(from));
+        }
+    }
+
+    // Kosaraju: order the nodes by finishing time, then collect the components on the reversed graph
+    let mut visited = vec![false; num_nodes];
+    let mut order = Vec::with_capacity(num_nodes);
+    for start in 0..num_nodes {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![(start, 0)];
+        while let Some((v, next)) = stack.last_mut() {
+            if let Some(&w) = graph[*v].get(*next) {
+                *next += 1;
+                if !visited[w] {
+                    visited[w] = true;
+                    stack.push((w, 0));
+                }
+            } else {
+                order.push(*v);
+                stack.pop();
+            }
+        }
+    }
+
+    let mut component = vec![usize::MAX; num_nodes];
+    for (c, &start) in order.iter().rev().enumerate() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        component[start] = c;
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            for &w in &reversed[v] {
+                if component[w] == usize::MAX {
+                    component[w] = c;
+                    stack.push(w);
+                }
+            }
+        }
+    }
+
+    (0..instance.num_vars()).all(|var| component[2 * var] != component[2 * var + 1])
+}
+
+/// A DPLL solver: unit propagation and chronological backtracking over a partial assignment.
+struct Dpll<'a> {
+    clauses: &'a [Clause],
+}
+
+impl Dpll<'_> {
+    /// Propagates the unit clauses under `values` until fixpoint.
+    /// Returns false on conflict, that is if a clause has all its literals false.
+    fn propagate(&self, values: &mut [Option<bool>]) -> bool {
+        self.propagate_counted(values, &mut SolveStats::default())
+    }
+
+    /// Propagates the unit clauses under `values` until fixpoint like `propagate`,
+    /// counting the propagations and the conflict in `stats`.
+    fn propagate_counted(&self, values: &mut [Option<bool>], stats: &mut SolveStats) -> bool {
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for clause in self.clauses {
+                let mut unassigned = None;
+                let mut unassigned_count = 0;
+                let mut satisfied = false;
+
+                for lit in clause.get_literals() {
+                    match values[lit.index()] {
+                        Some(v) if v != lit.is_negated() => {
+                            satisfied = true;
+                            break;
+                        }
+                        Some(_) => {}
+                        None => {
+                            unassigned = Some(*lit);
+                            unassigned_count += 1;
+                        }
+                    }
+                }
+
+                if satisfied {
+                    continue;
+                }
+
+                match (unassigned_count, unassigned) {
+                    (0, _) => {
+                        stats.conflicts += 1;
+                        return false;
+                    }
+                    (1, Some(lit)) => {
+                        values[lit.index()] = Some(!lit.is_negated());
+                        stats.propagations += 1;
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns a variable of a clause not yet satisfied by `values`, or `None` if all clauses are satisfied.
+    fn pick_branch(&self, values: &[Option<bool>]) -> Option<usize> {
+        self.clauses
+            .iter()
+            .filter(|clause| {
+                !clause
+                    .get_literals()
+                    .iter()
+                    .any(|lit| values[lit.index()] == Some(!lit.is_negated()))
+            })
+            .flat_map(Clause::get_literals)
+            .find(|lit| values[lit.index()].is_none())
+            .map(Literal::index)
+    }
+
+    /// Searches for a satisfying extension of `values`, which holds the model on success.
+    fn search(&self, values: &mut Vec<Option<bool>>) -> bool {
+        self.search_counted(values, &mut SolveStats::default())
+    }
+
+    /// Searches for a satisfying extension of `values` like `search`, counting the decisions,
+    /// conflicts and propagations in `stats`.
+    fn search_counted(&self, values: &mut Vec<Option<bool>>, stats: &mut SolveStats) -> bool {
+        if !self.propagate_counted(values, stats) {
+            return false;
+        }
+
+        let Some(var) = self.pick_branch(values) else {
+            return true;
+        };
+
+        for value in [true, false] {
+            let mut branch = values.clone();
+            branch[var] = Some(value);
+            stats.decisions += 1;
+
+            if self.search_counted(&mut branch, stats) {
+                *values = branch;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Searches for a satisfying extension of `values` like `search`, but branching on a random variable
+    /// of the unsatisfied clauses with a random polarity first. Holds the model on success.
+    #[cfg(feature = "rand")]
+    fn search_random<R: Rng>(&self, values: &mut Vec<Option<bool>>, rng: &mut R) -> bool {
+        if !self.propagate(values) {
+            return false;
+        }
+
+        let candidates: Vec<_> = self
+            .clauses
+            .iter()
+            .filter(|clause| {
+                !clause
+                    .get_literals()
+                    .iter()
+                    .any(|lit| values[lit.index()] == Some(!lit.is_negated()))
+            })
+            .flat_map(Clause::get_literals)
+            .filter(|lit| values[lit.index()].is_none())
+            .map(Literal::index)
+            .collect();
+        if candidates.is_empty() {
+            return true;
+        }
+
+        let var = candidates[rng.gen_range(0..candidates.len())];
+        let first: bool = rng.gen();
+        for value in [first, !first] {
+            let mut branch = values.clone();
+            branch[var] = Some(value);
+
+            if self.search_random(&mut branch, rng) {
+                *values = branch;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Calls `f` on each complete extension of `values` satisfying all clauses, until `f` breaks.
+    fn enumerate<F>(&self, values: &mut [Option<bool>], f: &mut F) -> ControlFlow<()>
+    where
+        F: FnMut(&BoolVec) -> ControlFlow<()>,
+    {
+        if !self.propagate(values) {
+            return ControlFlow::Continue(());
+        }
+
+        let Some(var) = self.pick_branch(values) else {
+            // All clauses are satisfied, every value of the remaining variables gives a model
+            let free: Vec<_> = (0..values.len()).filter(|&v| values[v].is_none()).collect();
+            let mut model = boolvec![false; values.len()];
+            for (i, value) in values.iter().enumerate() {
+                model.set(i, value.unwrap_or(false));
+            }
+
+            return enumerate_free(&mut model, &free, f);
+        };
+
+        for value in [true, false] {
+            let mut branch = values.to_vec();
+            branch[var] = Some(value);
+            self.enumerate(&mut branch, f)?;
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    /// Counts the extensions of `values` satisfying all clauses, over the unassigned variables among `vars`.
+    /// The clauses must only refer to `vars`.
+    fn count(&self, values: &mut [Option<bool>], vars: &[usize]) -> u128 {
+        if !self.propagate(values) {
+            return 0;
+        }
+
+        let Some(var) = self.pick_branch(values) else {
+            let free = vars.iter().filter(|&&v| values[v].is_none()).count();
+            return 1u128
+                .checked_shl(free as u32)
+                .expect("model count overflows u128");
+        };
+
+        [true, false]
+            .into_iter()
+            .map(|value| {
+                let mut branch = values.to_vec();
+                branch[var] = Some(value);
+                self.count(&mut branch, vars)
+            })
+            .fold(0u128, |acc, count| {
+                acc.checked_add(count).expect("model count overflows u128")
+            })
+    }
+
+    /// Counts the distinct values of the variables `projected` over the extensions of `values` satisfying all clauses,
+    /// by branching only on `projected` and checking the other variables for one extension.
+    fn count_projected(&self, values: &mut Vec<Option<bool>>, projected: &[usize]) -> u128 {
+        if !self.propagate(values) {
+            return 0;
+        }
+
+        if self.pick_branch(values).is_none() {
+            let free = projected.iter().filter(|&&v| values[v].is_none()).count();
+            return 1u128
+                .checked_shl(free as u32)
+                .expect("model count overflows u128");
+        }
+
+        let Some(&var) = projected.iter().find(|&&v| values[v].is_none()) else {
+            return self.search(values) as u128;
+        };
+
+        [true, false]
+            .into_iter()
+            .map(|value| {
+                let mut branch = values.clone();
+                branch[var] = Some(value);
+                self.count_projected(&mut branch, projected)
+            })
+            .fold(0u128, |acc, count| {
+                acc.checked_add(count).expect("model count overflows u128")
+            })
+    }
+}
+
+/// Checks every extension of `a` over `vars` for `Instance::is_backdoor`.
+fn is_backdoor_rec(
+    instance: &Instance,
+    vars: &[usize],
+    a: &mut Assignment,
+    sub: SubSolver,
+) -> bool {
+    let Some((&var, rest)) = vars.split_first() else {
+        return sub.decide(&instance.simplify_under(a)).is_some();
+    };
+
+    let decided = [false, true].into_iter().all(|value| {
+        a.set(var, value).expect("backdoor variable not present");
+        is_backdoor_rec(instance, rest, a, sub)
+    });
+    a.unset(var);
+
+    decided
+}
+
+/// Calls `f` on each value of the `free` variables of `model`, until `f` breaks.
+fn enumerate_free<F>(model: &mut BoolVec, free: &[usize], f: &mut F) -> ControlFlow<()>
+where
+    F: FnMut(&BoolVec) -> ControlFlow<()>,
+{
+    let Some((&var, rest)) = free.split_first() else {
+        return f(model);
+    };
+
+    for value in [false, true] {
+        model.set(var, value);
+        enumerate_free(model, rest, f)?;
+    }
+    model.set(var, false);
+
+    ControlFlow::Continue(())
+}
+
+impl Instance {
+    /// Solves the instance with a DPLL search.
+    /// Panics if a literal is not present in the variables.
+    pub fn solve(&self) -> SolveResult {
+        self.solve_under_assumptions(&[])
+    }
+
+    /// Solves the instance with the given literals forced to true.
+    /// Returns `Unsat` if the instance has no model satisfying all the assumptions.
+    /// Panics if a literal is not present in the variables.
+    pub fn solve_under_assumptions(&self, assumptions: &[Literal]) -> SolveResult {
+        self.solve_counted(assumptions, &mut SolveStats::default())
+    }
+
+    /// Solves the instance like `solve`, and returns the statistics of the search along with the result,
+    /// as a machine-independent measure of the hardness of the instance.
+    /// Panics if a literal is not present in the variables.
+    pub fn solve_instrumented(&self) -> (SolveResult, SolveStats) {
+        let mut stats = SolveStats::default();
+        let start = Instant::now();
+        let result = self.solve_counted(&[], &mut stats);
+        stats.duration = start.elapsed();
+
+        (result, stats)
+    }
+
+    /// Solves the instance under the assumptions, counting the search statistics in `stats`.
+    fn solve_counted(&self, assumptions: &[Literal], stats: &mut SolveStats) -> SolveResult {
+        let mut values = vec![None; self.num_vars()];
+
+        for lit in assumptions {
+            let value = Some(!lit.is_negated());
+            if values[lit.index()].is_some_and(|v| Some(v) != value) {
+                return SolveResult::Unsat;
+            }
+            values[lit.index()] = value;
+        }
+
+        let solver = Dpll {
+            clauses: self.get_clauses(),
+        };
+        if !solver.search_counted(&mut values, stats) {
+            return SolveResult::Unsat;
+        }
+
+        let mut model = boolvec![false; values.len()];
+        for (i, value) in values.into_iter().enumerate() {
+            model.set(i, value.unwrap_or(false));
+        }
+
+        SolveResult::Sat(model)
+    }
+
+    /// Returns, for each clause, whether removing it changes the satisfiability of the instance.
+    /// Removing a clause cannot make a satisfiable instance unsatisfiable, so only the clauses of an unsatisfiable
+    /// instance can be critical: the ones whose removal makes it satisfiable, which are exactly the clauses
+    /// belonging to every unsatisfiable core (minimal unsatisfiable subset), and explain its infeasibility.
+    /// This costs one solver call per clause on top of the initial one, so up to `m + 1` searches.
+    /// Panics if a literal is not present in the variables.
+    pub fn clause_criticality(&self) -> Vec<bool> {
+        let clauses = self.get_clauses();
+        if self.solve().is_sat() {
+            return vec![false; clauses.len()];
+        }
+
+        (0..clauses.len())
+            .map(|i| {
+                let others: Vec<_> = clauses
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, clause)| clause.clone())
+                    .collect();
+                Dpll { clauses: &others }.search(&mut vec![None; self.num_vars()])
+            })
+            .collect()
+    }
+
+    /// Returns a random model of the instance, or `None` if it is unsatisfiable.
+    /// The DPLL search branches on random variables with random polarities, and the variables left free
+    /// are set at random, which spreads the models much better than `solve` but is not provably uniform.
+    /// Panics if a literal is not present in the variables.
+    #[cfg(feature = "rand")]
+    pub fn sample_model<R: Rng>(&self, rng: &mut R) -> Option<BoolVec> {
+        let solver = Dpll {
+            clauses: self.get_clauses(),
+        };
+        let mut values = vec![None; self.num_vars()];
+        if !solver.search_random(&mut values, rng) {
+            return None;
+        }
+
+        let mut model = boolvec![false; values.len()];
+        for (i, value) in values.into_iter().enumerate() {
+            model.set(i, value.unwrap_or_else(|| rng.gen()));
+        }
+
+        Some(model)
+    }
+
+    /// Returns true if the clause is entailed by the instance, that is if the instance conjoined with
+    /// the logical negation of the clause is unsatisfiable.
+    /// The negation is passed to the solver as assumptions rather than copying the instance.
+    pub fn entails(&self, clause: &Clause) -> bool {
+        let negation: Vec<_> = clause
+            .logical_negation()
+            .iter()
+            .filter_map(Clause::unit_literal)
+            .collect();
+
+        self.solve_under_assumptions(&negation).is_unsat()
+    }
+
+    /// Returns true if both instances are satisfiable or both are unsatisfiable, by solving each of them.
+    /// Meant as a test oracle for satisfiability-preserving transformations, the verdict is only as reliable
+    /// as `solve` on the two instances: an `Unknown` result never compares equal.
+    /// Panics if a literal is not present in the variables of its instance.
+    pub fn equisatisfiable(&self, other: &Instance) -> bool {
+        matches!(
+            (self.solve(), other.solve()),
+            (SolveResult::Sat(_), SolveResult::Sat(_)) | (SolveResult::Unsat, SolveResult::Unsat)
+        )
+    }
+
+    /// Calls `f` on each model of the instance, found by backtracking, until `f` returns `Break`.
+    /// Models are complete assignments and each is passed once.
+    /// Panics if a literal is not present in the variables.
+    pub fn for_each_model(&self, mut f: impl FnMut(&BoolVec) -> ControlFlow<()>) {
+        let solver = Dpll {
+            clauses: self.get_clauses(),
+        };
+
+        let _ = solver.enumerate(&mut vec![None; self.num_vars()], &mut f);
+    }
+
+    /// Returns the number of models of the instance, by exhaustive DPLL search.
+    /// Panics if the count overflows `u128` or if a literal is not present in the variables.
+    pub fn count_models(&self) -> u128 {
+        let solver = Dpll {
+            clauses: self.get_clauses(),
+        };
+        let vars: Vec<_> = (0..self.num_vars()).collect();
+
+        solver.count(&mut vec![None; self.num_vars()], &vars)
+    }
+
+    /// Returns the number of assignments of the variables `vars` that extend to a model of the instance,
+    /// that is the number of distinct projections of the models onto `vars` (projected model counting).
+    /// Repeated variables are counted once.
+    /// Panics if the count overflows `u128` or if a variable or a literal is not present in the variables.
+    pub fn count_models_projected(&self, vars: &[usize]) -> u128 {
+        let mut projected = vars.to_vec();
+        projected.sort_unstable();
+        projected.dedup();
+
+        let solver = Dpll {
+            clauses: self.get_clauses(),
+        };
+
+        solver.count_projected(&mut vec![None; self.num_vars()], &projected)
+    }
+
+    /// Returns the number of models of the instance, as the product of the model counts of its components.
+    /// Components are the connected components of the variables linked by sharing a clause,
+    /// and are counted independently, which avoids branching over unrelated variables.
+    /// A variable appearing in no clause is a component of its own, with 2 models.
+    /// Panics if the count overflows `u128` or if a literal is not present in the variables.
+    pub fn count_models_decomposed(&self) -> u128 {
+        // Components have disjoint variables, so they can share the partial assignment
+        let mut values = vec![None; self.num_vars()];
+
+        self.components()
+            .into_iter()
+            .map(|(vars, clause_indices)| {
+                let clauses: Vec<_> = clause_indices
+                    .into_iter()
+                    .map(|i| self.get_clauses()[i].clone())
+                    .collect();
+                Dpll { clauses: &clauses }.count(&mut values, &vars)
+            })
+            .try_fold(1u128, |acc, count| acc.checked_mul(count))
+            .expect("model count overflows u128")
+    }
+
+    /// Returns the connected components of the primal graph, as their variables and the indices of their clauses.
+    /// Empty clauses form a component without variables.
+    fn components(&self) -> Vec<(Vec<usize>, Vec<usize>)> {
+        let n = self.num_vars();
+        let mut sets = UnionFind::new(n);
+
+        for clause in self.get_clauses() {
+            let mut literals = clause.get_literals().iter();
+            if let Some(first) = literals.next() {
+                for lit in literals {
+                    sets.union(first.index(), lit.index());
+                }
+            }
+        }
+
+        let mut component_of = vec![usize::MAX; n];
+        let mut components: Vec<(Vec<usize>, Vec<usize>)> = vec![];
+        for var in 0..n {
+            let root = sets.find(var);
+            if component_of[root] == usize::MAX {
+                component_of[root] = components.len();
+                components.push((vec![], vec![]));
+            }
+            components[component_of[root]].0.push(var);
+        }
+
+        let mut empty = vec![];
+        for (i, clause) in self.get_clauses().iter().enumerate() {
+            match clause.get_literals().first() {
+                Some(lit) => {
+                    let root = sets.find(lit.index());
+                    components[component_of[root]].1.push(i);
+                }
+                None => empty.push(i),
+            }
+        }
+        if !empty.is_empty() {
+            components.push((vec![], empty));
+        }
+
+        components
+    }
+
+    /// Returns whether `vars` is a strong backdoor of the instance for `sub`, that is whether every assignment of `vars`
+    /// leaves a residual instance (see `simplify_under`) decided by `sub`.
+    /// The `2^vars.len()` assignments are enumerated, so the cost is exponential in the size of the candidate set.
+    /// Panics if a variable is not present in the variables.
+    pub fn is_backdoor(&self, vars: &[usize], sub: SubSolver) -> bool {
+        let mut vars = vars.to_vec();
+        vars.sort_unstable();
+        vars.dedup();
+
+        let mut a = Assignment::new(self.num_vars());
+        is_backdoor_rec(self, &vars, &mut a, sub)
+    }
+
+    /// Returns the backbone of the instance, that is the literals true in every model,
+    /// or an empty vector if the instance is unsatisfiable.
+    /// This naive version requires one solver call per variable on top of the initial one.
+    pub fn backbone(&self) -> Vec<Literal> {
+        let SolveResult::Sat(model) = self.solve() else {
+            return vec![];
+        };
+
+        (0..self.num_vars())
+            .map(|i| Literal::new(i, !model.get(i).unwrap()))
+            .filter(|lit| self.solve_under_assumptions(&[lit.negated()]).is_unsat())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pigeonhole_2_in_1() -> Instance {
+        Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1]),
+                Clause::from_cnf(vec![2]),
+                Clause::from_cnf(vec![-1, -2]),
+            ],
+        )
+    }
+
+    #[test]
+    fn solve() {
+        let mut instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 3]),
+                Clause::from_cnf(vec![-3, -2]),
+                Clause::from_cnf(vec![-2]),
+            ],
+        );
+
+        let SolveResult::Sat(model) = instance.solve() else {
+            panic!("instance is satisfiable");
+        };
+        instance.vars = model;
+        assert!(instance.is_sat());
+
+        assert!(pigeonhole_2_in_1().solve().is_unsat());
+        assert!(Instance::with_clauses(0, vec![]).solve().is_sat());
+        assert!(Instance::with_clauses(1, vec![Clause::from_cnf(vec![])])
+            .solve()
+            .is_unsat());
+    }
+
+    #[test]
+    fn competition_output() {
+        let output = |result: SolveResult, num_vars| {
+            let mut out = vec![];
+            result.to_competition_output(&mut out, num_vars).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(
+            output(SolveResult::Sat(BoolVec::from([true, false, true])), 3),
+            "s SATISFIABLE\nv 1 -2 3 0\n"
+        );
+        assert_eq!(
+            output(SolveResult::Sat(BoolVec::from([true, false, true])), 0),
+            "s SATISFIABLE\nv 0\n"
+        );
+        assert_eq!(output(SolveResult::Unsat, 3), "s UNSATISFIABLE\n");
+        assert_eq!(output(SolveResult::Unknown, 3), "s UNKNOWN\n");
+    }
+
+    #[test]
+    fn solve_instrumented() {
+        let (result, stats) = pigeonhole_2_in_1().solve_instrumented();
+        assert!(result.is_unsat());
+        assert_eq!(
+            (stats.decisions, stats.conflicts, stats.propagations),
+            (0, 1, 2)
+        );
+
+        // x1 is branched on first, true then false
+        let instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![-2, -1]),
+            ],
+        );
+        let (result, stats) = instance.solve_instrumented();
+        assert_eq!(result, instance.solve());
+        assert_eq!(
+            (stats.decisions, stats.conflicts, stats.propagations),
+            (2, 1, 2)
+        );
+    }
+
+    #[test]
+    fn assumptions() {
+        let instance = Instance::with_clauses(2, vec![Clause::from_cnf(vec![1, 2])]);
+
+        assert!(instance
+            .solve_under_assumptions(&[Literal::from_cnf(-1)])
+            .model()
+            .is_some_and(|model| model.get(1) == Some(true)));
+        assert!(instance
+            .solve_under_assumptions(&[Literal::from_cnf(-1), Literal::from_cnf(-2)])
+            .is_unsat());
+        assert!(instance
+            .solve_under_assumptions(&[Literal::from_cnf(1), Literal::from_cnf(-1)])
+            .is_unsat());
+    }
+
+    #[test]
+    fn sub_solvers() {
+        let two_sat = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![1, -2]),
+            ],
+        );
+        assert_eq!(SubSolver::TwoSat.decide(&two_sat), Some(true));
+        assert_eq!(SubSolver::UnitProp.decide(&two_sat), None);
+        assert_eq!(SubSolver::Horn.decide(&two_sat), None);
+
+        let mut unsat = two_sat.clone();
+        unsat.add_clause(Clause::from_cnf(vec![-1, -2]));
+        assert_eq!(SubSolver::TwoSat.decide(&unsat), Some(false));
+        assert_eq!(SubSolver::TwoSat.decide(&pigeonhole_2_in_1()), Some(false));
+
+        let mut wide = two_sat.clone();
+        wide.add_clause(Clause::from_cnf(vec![-1, -2, 3]));
+        assert_eq!(SubSolver::TwoSat.decide(&wide), None);
+
+        let horn = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![-1, 2]),
+                Clause::from_cnf(vec![1]),
+                Clause::from_cnf(vec![-2, -3]),
+            ],
+        );
+        assert_eq!(SubSolver::Horn.decide(&horn), Some(true));
+        assert_eq!(SubSolver::UnitProp.decide(&horn), Some(true));
+
+        let mut horn_unsat = horn.clone();
+        horn_unsat.add_clause(Clause::from_cnf(vec![3]));
+        assert_eq!(SubSolver::Horn.decide(&horn_unsat), Some(false));
+        assert_eq!(SubSolver::UnitProp.decide(&horn_unsat), Some(false));
+
+        // Propagation leaves both variables unassigned, setting them to false satisfies the clauses
+        let horn_open = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![-1, -2]),
+                Clause::from_cnf(vec![-1, 2]),
+            ],
+        );
+        assert_eq!(SubSolver::UnitProp.decide(&horn_open), None);
+        assert_eq!(SubSolver::Horn.decide(&horn_open), Some(true));
+    }
+
+    #[test]
+    fn is_backdoor() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2, 3]),
+                Clause::from_cnf(vec![-1, -2, 3]),
+            ],
+        );
+
+        assert!(!instance.is_backdoor(&[], SubSolver::TwoSat));
+        assert!(instance.is_backdoor(&[0], SubSolver::TwoSat));
+        assert!(!instance.is_backdoor(&[0], SubSolver::UnitProp));
+        assert!(instance.is_backdoor(&[0, 1, 0], SubSolver::UnitProp));
+        assert!(!instance.is_backdoor(&[2], SubSolver::UnitProp));
+    }
+
+    #[test]
+    fn count_models_projected() {
+        // x2 <-> (x0 or x1), with x3 free
+        let instance = Instance::with_clauses(
+            4,
+            vec![
+                Clause::from_cnf(vec![-3, 1, 2]),
+                Clause::from_cnf(vec![3, -1]),
+                Clause::from_cnf(vec![3, -2]),
+            ],
+        );
+
+        assert_eq!(instance.count_models(), 8);
+        assert_eq!(instance.count_models_projected(&[2]), 2);
+        assert_eq!(instance.count_models_projected(&[0, 1]), 4);
+        assert_eq!(instance.count_models_projected(&[0, 2, 0]), 3);
+        assert_eq!(instance.count_models_projected(&[3]), 2);
+        assert_eq!(instance.count_models_projected(&[]), 1);
+
+        assert_eq!(pigeonhole_2_in_1().count_models_projected(&[0]), 0);
+    }
+
+    #[test]
+    fn count_models() {
+        let instance = Instance::with_clauses(
+            5,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, -2]),
+                Clause::from_cnf(vec![3, 4]),
+            ],
+        );
+        // 2 models over {x0, x1}, 3 over {x2, x3}, and x4 is free
+        assert_eq!(instance.count_models(), 12);
+        assert_eq!(instance.count_models_decomposed(), 12);
+        assert_eq!(instance.count_models_projected(&[0, 1, 2, 3, 4]), 12);
+
+        assert_eq!(pigeonhole_2_in_1().count_models(), 0);
+        assert_eq!(pigeonhole_2_in_1().count_models_decomposed(), 0);
+
+        let with_empty =
+            Instance::with_clauses(2, vec![Clause::from_cnf(vec![1]), Clause::from_cnf(vec![])]);
+        assert_eq!(with_empty.count_models(), 0);
+        assert_eq!(with_empty.count_models_decomposed(), 0);
+
+        assert_eq!(Instance::with_clauses(0, vec![]).count_models(), 1);
+        assert_eq!(
+            Instance::with_clauses(0, vec![]).count_models_decomposed(),
+            1
+        );
+        assert_eq!(
+            Instance::with_clauses(3, vec![]).count_models_decomposed(),
+            8
+        );
+    }
+
+    #[test]
+    fn for_each_model() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![Clause::from_cnf(vec![1, 2]), Clause::from_cnf(vec![-1, -2])],
+        );
+
+        let mut models = vec![];
+        instance.for_each_model(|model| {
+            let mut check = instance.clone();
+            check.vars = BoolVec::from(model.into_vec());
+            assert!(check.is_sat());
+
+            models.push(model.into_vec());
+            ControlFlow::Continue(())
+        });
+        models.sort();
+        models.dedup();
+        assert_eq!(models.len(), 4);
+
+        let mut visited = 0;
+        instance.for_each_model(|_| {
+            visited += 1;
+            if visited == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(visited, 3);
+
+        pigeonhole_2_in_1().for_each_model(|_| panic!("instance is unsatisfiable"));
+    }
+
+    #[test]
+    fn entails() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![Clause::from_cnf(vec![-1, 2]), Clause::from_cnf(vec![-2, 3])],
+        );
+
+        assert!(instance.entails(&Clause::from_cnf(vec![-1, 3])));
+        assert!(instance.entails(&Clause::from_cnf(vec![1, -1])));
+        assert!(!instance.entails(&Clause::from_cnf(vec![3])));
+        assert!(pigeonhole_2_in_1().entails(&Clause::from_cnf(vec![])));
+        assert!(!instance.entails(&Clause::from_cnf(vec![])));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sample_model() {
+        use rand::{rngs::StdRng, SeedableRng};
+        use std::collections::HashSet;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1, -2, 3]),
+            ],
+        );
+
+        let mut models = HashSet::new();
+        for _ in 0..200 {
+            let model = instance.sample_model(&mut rng).unwrap();
+            assert!(instance.clauses().all(|clause| clause.test_sat(&model)));
+            models.insert(model.into_vec());
+        }
+        assert_eq!(models.len() as u128, instance.count_models());
+
+        assert_eq!(pigeonhole_2_in_1().sample_model(&mut rng), None);
+    }
+
+    #[test]
+    fn clause_criticality() {
+        // [1, 2] is in no unsatisfiable core, [1] and [-1] are in the only one
+        let mut instance = Instance::with_clauses(
+            2,
+            vec![
+                Clause::from_cnf(vec![1]),
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1]),
+            ],
+        );
+        assert_eq!(instance.clause_criticality(), vec![true, false, true]);
+
+        // Two cores, [-1] with either [1] or both [2] and [-2, 1]: only [-1] is in both
+        instance.add_clause(Clause::from_cnf(vec![2]));
+        instance.add_clause(Clause::from_cnf(vec![-2, 1]));
+        assert_eq!(
+            instance.clause_criticality(),
+            vec![false, false, true, false, false]
+        );
+
+        assert_eq!(
+            Instance::with_clauses(1, vec![Clause::from_cnf(vec![1])]).clause_criticality(),
+            vec![false]
+        );
+    }
+
+    #[test]
+    fn equisatisfiable() {
+        let sat = Instance::with_clauses(2, vec![Clause::from_cnf(vec![1, -2])]);
+        let tautologies = Instance::with_clauses(1, vec![Clause::from_cnf(vec![1, -1])]);
+
+        assert!(sat.equisatisfiable(&tautologies));
+        assert!(pigeonhole_2_in_1()
+            .equisatisfiable(&Instance::with_clauses(0, vec![Clause::from_cnf(vec![])])));
+        assert!(!sat.equisatisfiable(&pigeonhole_2_in_1()));
+    }
+
+    #[test]
+    fn backbone() {
+        let instance = Instance::with_clauses(
+            3,
+            vec![
+                Clause::from_cnf(vec![1]),
+                Clause::from_cnf(vec![-1, -2]),
+                Clause::from_cnf(vec![2, 3, -3]),
+            ],
+        );
+
+        assert_eq!(
+            instance.backbone(),
+            vec![Literal::from_cnf(1), Literal::from_cnf(-2)]
+        );
+        assert!(pigeonhole_2_in_1().backbone().is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{clause::Clause, instance::Instance};
+
+/// Statistics of the clauses of an instance, see `Instance::stats` and `StatsAccumulator`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceStats {
+    /// The number of clauses.
+    pub num_clauses: usize,
+    /// The length of the shortest clause, 0 without clauses.
+    pub min_len: usize,
+    /// The length of the longest clause, 0 without clauses.
+    pub max_len: usize,
+    /// The average length of the clauses, NaN without clauses.
+    pub mean_len: f64,
+    /// The number of unit clauses.
+    pub num_units: usize,
+    /// The number of Horn clauses, with at most one positive literal.
+    pub num_horn: usize,
+    /// The largest variable index appearing in the clauses, `None` if no clause has a literal.
+    pub max_var: Option<usize>,
+}
+
+/// Computes `InstanceStats` one clause at a time, so that the clauses of a file too large to fit
+/// in memory can be analyzed while streamed (see `Instance::stream_clauses`).
+#[derive(Debug, Clone, Default)]
+pub struct StatsAccumulator {
+    num_clauses: usize,
+    min_len: Option<usize>,
+    max_len: usize,
+    num_literals: usize,
+    num_units: usize,
+    num_horn: usize,
+    max_var: Option<usize>,
+}
+
+impl StatsAccumulator {
+    /// Creates an accumulator without clauses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accounts for a clause.
+    pub fn push(&mut self, clause: &Clause) {
+        self.num_clauses += 1;
+        self.min_len = Some(
+            self.min_len
+                .map_or(clause.len(), |min| min.min(clause.len())),
+        );
+        self.max_len = self.max_len.max(clause.len());
+        self.num_literals += clause.len();
+        self.num_units += clause.is_unit() as usize;
+        self.num_horn += clause.is_horn() as usize;
+
+        let max_var = clause.get_literals().iter().map(|lit| lit.index()).max();
+        self.max_var = self.max_var.max(max_var);
+    }
+
+    /// Returns the statistics of the clauses pushed so far.
+    pub fn finish(&self) -> InstanceStats {
+        InstanceStats {
+            num_clauses: self.num_clauses,
+            min_len: self.min_len.unwrap_or(0),
+            max_len: self.max_len,
+            mean_len: self.num_literals as f64 / self.num_clauses as f64,
+            num_units: self.num_units,
+            num_horn: self.num_horn,
+            max_var: self.max_var,
+        }
+    }
+}
+
+impl Instance {
+    /// Returns the statistics of the clauses of the instance.
+    pub fn stats(&self) -> InstanceStats {
+        let mut accumulator = StatsAccumulator::new();
+        for clause in self.clauses() {
+            accumulator.push(clause);
+        }
+
+        accumulator.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let instance = Instance::with_clauses(
+            5,
+            vec![
+                Clause::from_cnf(vec![1, -2, 3]),
+                Clause::from_cnf(vec![-4]),
+                Clause::from_cnf(vec![2, 3]),
+                Clause::from_cnf(vec![-1, -2]),
+            ],
+        );
+
+        assert_eq!(
+            instance.stats(),
+            InstanceStats {
+                num_clauses: 4,
+                min_len: 1,
+                max_len: 3,
+                mean_len: 2.0,
+                num_units: 1,
+                num_horn: 2,
+                max_var: Some(3),
+            }
+        );
+
+        let empty = Instance::with_clauses(2, vec![]).stats();
+        assert_eq!(
+            (empty.num_clauses, empty.min_len, empty.max_var),
+            (0, 0, None)
+        );
+        assert!(empty.mean_len.is_nan());
+    }
+
+    #[test]
+    fn streamed() {
+        let cnf = "c comment\np cnf 3 3\n1 -2 0\nc inner comment\n\n-3 0\n2 3 1 0\n";
+        let (header, clauses) = Instance::stream_clauses(cnf.as_bytes()).unwrap();
+        assert_eq!(header, (3, 3));
+
+        let mut accumulator = StatsAccumulator::new();
+        for clause in clauses {
+            accumulator.push(&clause.unwrap());
+        }
+        assert_eq!(
+            accumulator.finish(),
+            Instance::from_reader(cnf.replace("c inner comment\n\n", "").as_bytes())
+                .unwrap()
+                .stats()
+        );
+
+        let (_, clauses) = Instance::stream_clauses("p cnf 1 1\n1 x 0\n".as_bytes()).unwrap();
+        assert!(clauses.collect::<Result<Vec<_>, _>>().is_err());
+        assert!(Instance::stream_clauses("1 0\n".as_bytes()).is_err());
+
+        let (header, clauses) =
+            Instance::stream_clauses("\n \np cnf 1 1\n1 0\n".as_bytes()).unwrap();
+        assert_eq!(header, (1, 1));
+        assert_eq!(
+            clauses.collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![Clause::from_cnf(vec![1])]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use bool_vec::BoolVec;
+
+use crate::instance::Instance;
+
+/// The truth table of an instance, displayed with a column per variable and a last column for the formula,
+/// see `Instance::display_truth_table`.
+#[derive(Debug)]
+pub struct TruthTable {
+    num_vars: usize,
+    rows: Vec<(BoolVec, bool)>,
+}
+
+impl Instance {
+    /// Returns the truth table of the formula: every assignment of the variables, in binary counting order
+    /// with the variable 0 as the most significant bit, along with whether it satisfies all clauses.
+    /// The table has `2^num_vars()` rows, so this is only meant for tiny instances,
+    /// and debug builds panic above 20 variables.
+    pub fn truth_table(&self) -> Vec<(BoolVec, bool)> {
+        let n = self.num_vars();
+        debug_assert!(n <= 20, "truth table of {n} variables is too large");
+
+        (0..1usize << n)
+            .map(|row| {
+                let vars = BoolVec::from(
+                    (0..n)
+                        .map(|i| row >> (n - 1 - i) & 1 != 0)
+                        .collect::<Vec<_>>(),
+                );
+                let sat = self.clauses().all(|clause| clause.test_sat(&vars));
+                (vars, sat)
+            })
+            .collect()
+    }
+
+    /// Returns the truth table of the formula for display, see `truth_table`.
+    pub fn display_truth_table(&self) -> TruthTable {
+        TruthTable {
+            num_vars: self.num_vars(),
+            rows: self.truth_table(),
+        }
+    }
+}
+
+impl fmt::Display for TruthTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<_> = (0..self.num_vars).map(|i| format!("x{i}")).collect();
+        for name in &names {
+            write!(f, "{name} ")?;
+        }
+        writeln!(f, "| F")?;
+
+        for (vars, sat) in &self.rows {
+            for (name, value) in names.iter().zip(vars) {
+                write!(f, "{:>width$} ", value as u8, width = name.len())?;
+            }
+            writeln!(f, "| {}", *sat as u8)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clause::Clause;
+
+    #[test]
+    fn truth_table() {
+        let instance = Instance::with_clauses(
+            2,
+            vec![Clause::from_cnf(vec![1, 2]), Clause::from_cnf(vec![-1])],
+        );
+
+        let table: Vec<_> = instance
+            .truth_table()
+            .into_iter()
+            .map(|(vars, sat)| (vars.into_vec(), sat))
+            .collect();
+        assert_eq!(
+            table,
+            vec![
+                (vec![false, false], false),
+                (vec![false, true], true),
+                (vec![true, false], false),
+                (vec![true, true], false),
+            ]
+        );
+
+        assert_eq!(
+            instance.display_truth_table().to_string(),
+            "x0 x1 | F\n 0  0 | 0\n 0  1 | 1\n 1  0 | 0\n 1  1 | 0\n"
+        );
+        assert_eq!(Instance::with_clauses(0, vec![]).truth_table().len(), 1);
+    }
+}
@@ -0,0 +1,47 @@
+/// A disjoint-set forest over `0..n`, with path halving.
+#[derive(Debug, Clone)]
+pub(crate) struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    /// Creates `n` singleton sets.
+    pub(crate) fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+        }
+    }
+
+    /// Returns the representative of the set of `i`.
+    pub(crate) fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    /// Merges the sets of `i` and `j`, the smallest representative representing the union.
+    pub(crate) fn union(&mut self, i: usize, j: usize) {
+        let (i, j) = (self.find(i), self.find(j));
+        let (root, child) = if i < j { (i, j) } else { (j, i) };
+        self.parents[child] = root;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union() {
+        let mut sets = UnionFind::new(5);
+        sets.union(3, 1);
+        sets.union(4, 3);
+
+        assert_eq!(sets.find(4), 1);
+        assert_eq!(sets.find(3), 1);
+        assert_eq!(sets.find(0), 0);
+        assert_eq!(sets.find(2), 2);
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use bool_vec::BoolVec;
+
+use crate::literal::Literal;
+
+/// Interns variable names to variable indices, to build clauses from names
+/// and to decode models back to named truth values.
+/// Indices are given in order of first appearance, starting at 0.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VarMap {
+    indices: HashMap<String, usize>,
+    names: Vec<String>,
+}
+
+impl VarMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of the variable `name`, interning it if it is new.
+    pub fn var(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+
+        let index = self.names.len();
+        self.indices.insert(name.to_owned(), index);
+        self.names.push(name.to_owned());
+        index
+    }
+
+    /// Returns the literal over the variable `name`, interning it if it is new.
+    pub fn literal(&mut self, name: &str, negated: bool) -> Literal {
+        Literal::new(self.var(name), negated)
+    }
+
+    /// Returns the index of the variable `name`, or `None` if it was never interned.
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Returns the name of the variable at `index`, or `None` if there is none.
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names.get(index).map(String::as_str)
+    }
+
+    /// Returns the number of interned variables.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns whether no variable was interned.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the named truth values of the model, for the variables both interned and in the model.
+    pub fn decode<'a>(&'a self, model: &BoolVec) -> Vec<(&'a str, bool)> {
+        self.names
+            .iter()
+            .zip(model)
+            .map(|(name, value)| (name.as_str(), value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clause::Clause, instance::Instance};
+
+    #[test]
+    fn interning() {
+        let mut map = VarMap::new();
+        assert!(map.is_empty());
+
+        let rain = map.literal("rain", false);
+        let wet = map.literal("wet", false);
+        let not_rain = map.literal("rain", true);
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(rain, not_rain.negated());
+        assert_eq!(map.index("wet"), Some(wet.index()));
+        assert_eq!(map.index("sun"), None);
+        assert_eq!(map.name(rain.index()), Some("rain"));
+        assert_eq!(map.name(2), None);
+    }
+
+    #[test]
+    fn decode() {
+        let mut map = VarMap::new();
+        let clauses = vec![
+            Clause::new(vec![map.literal("rain", true), map.literal("wet", false)]),
+            Clause::new(vec![map.literal("rain", false)]),
+        ];
+        let instance = Instance::with_clauses(map.len(), clauses);
+
+        let model = instance.solve();
+        assert_eq!(
+            map.decode(model.model().unwrap()),
+            vec![("rain", true), ("wet", true)]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    io::{self, Read},
+    ops::Add,
+    str::FromStr,
+};
+
+use bool_vec::BoolVec;
+
+use crate::{
+    clause::Clause,
+    instance::Instance,
+    parse::{parse_clause, parse_header, referred_vars, ParseOptions},
+};
+
+/// A SAT instance carrying a weight per clause, such as a cost or a probability.
+/// The weights are stored in parallel to the clauses of the underlying instance.
+#[derive(Debug, Clone)]
+pub struct WeightedInstance<W> {
+    instance: Instance,
+    weights: Vec<W>,
+}
+
+impl<W> WeightedInstance<W> {
+    /// Creates a new weighted instance, the weight `i` belonging to the clause `i`.
+    /// Panics if there are not as many weights as clauses.
+    pub fn new(instance: Instance, weights: Vec<W>) -> Self {
+        assert_eq!(instance.get_clauses().len(), weights.len());
+
+        Self { instance, weights }
+    }
+
+    /// Creates a new weighted instance with the given number of variables initialized at 0 and weighted clauses.
+    pub fn with_clauses(n: usize, weighted_clauses: Vec<(Clause, W)>) -> Self {
+        let (clauses, weights) = weighted_clauses.into_iter().unzip();
+
+        Self {
+            instance: Instance::with_clauses(n, clauses),
+            weights,
+        }
+    }
+
+    /// Returns a reference to the underlying instance
+    pub fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    /// Returns the weights, in clause order
+    pub fn weights(&self) -> &[W] {
+        &self.weights
+    }
+
+    /// Returns an iterator over the clauses and their weight
+    pub fn weighted_clauses(&self) -> impl Iterator<Item = (&Clause, &W)> {
+        self.instance.clauses().zip(&self.weights)
+    }
+
+    /// Consumes the weighted instance and returns the instance and the weights
+    pub fn into_parts(self) -> (Instance, Vec<W>) {
+        (self.instance, self.weights)
+    }
+}
+
+impl<W> WeightedInstance<W>
+where
+    W: Copy + Add<Output = W> + Default,
+{
+    /// Returns the sum of the weights of the clauses satisfied by the given variable values.
+    pub fn weighted_sat_sum(&self, vars: &BoolVec) -> W {
+        self.weighted_clauses()
+            .filter(|(clause, _)| clause.test_sat(vars))
+            .fold(W::default(), |acc, (_, &w)| acc + w)
+    }
+
+    /// Returns the sum of all weights.
+    pub fn total_weight(&self) -> W {
+        self.weights.iter().fold(W::default(), |acc, &w| acc + w)
+    }
+}
+
+impl WeightedInstance<u64> {
+    /// Splits the instance for MaxSAT, following the WCNF convention that the clauses of weight at least `top`
+    /// are hard: returns the instance of the hard clauses, with the same variables, and the soft clauses with their weight.
+    pub fn split_hard_soft(&self, top: u64) -> (Instance, Vec<(Clause, u64)>) {
+        let (hard, soft): (Vec<_>, Vec<_>) = self
+            .weighted_clauses()
+            .map(|(clause, &w)| (clause.clone(), w))
+            .partition(|&(_, w)| w >= top);
+
+        let hard = hard.into_iter().map(|(clause, _)| clause).collect();
+        let vars = BoolVec::from(self.instance.vars.into_vec());
+        (Instance::new(vars, hard), soft)
+    }
+}
+
+impl Instance {
+    /// Creates a weighted instance from a reader in WCNF, `p wcnf n m` followed by clauses led by their weight,
+    /// the weights being parsed as `W`, such as `u64` for MaxSAT or `f64` for log-probabilities.
+    /// The optional top weight of the header is ignored, see `WeightedInstance::split_hard_soft`.
+    /// Returns an error if the content is not in WCNF, if a weight or a clause is malformed,
+    /// or if a clause refers to an undeclared variable.
+    pub fn from_wcnf_reader<W: FromStr, R: Read>(mut reader: R) -> io::Result<WeightedInstance<W>> {
+        let options = ParseOptions {
+            problem_type: "wcnf".to_string(),
+            ..Default::default()
+        };
+
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        let mut lines = content.trim().lines();
+        let (n, m) = parse_header(&mut lines, &options).ok_or(io::ErrorKind::InvalidInput)?;
+
+        let weighted_clauses: Vec<_> = lines
+            .take(m)
+            .map(|line| {
+                let (weight, clause) = line.trim_start().split_once(char::is_whitespace)?;
+                Some((parse_clause(clause, &options)?, weight.parse().ok()?))
+            })
+            .collect::<Option<_>>()
+            .ok_or(io::ErrorKind::InvalidInput)?;
+
+        let weighted = WeightedInstance::with_clauses(n, weighted_clauses);
+        if referred_vars(weighted.instance.get_clauses()) > n {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+
+        Ok(weighted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_sum() {
+        let instance = WeightedInstance::with_clauses(
+            2,
+            vec![
+                (Clause::from_cnf(vec![1]), 3u64),
+                (Clause::from_cnf(vec![-1, 2]), 5),
+                (Clause::from_cnf(vec![-2]), 7),
+            ],
+        );
+
+        assert_eq!(instance.total_weight(), 15);
+        assert_eq!(
+            instance.weighted_sat_sum(&BoolVec::from([false, false])),
+            12
+        );
+        assert_eq!(instance.weighted_sat_sum(&BoolVec::from([true, true])), 8);
+
+        let (_, weights) = instance.into_parts();
+        assert_eq!(weights, vec![3, 5, 7]);
+    }
+
+    #[test]
+    fn split_hard_soft() {
+        let instance = WeightedInstance::with_clauses(
+            2,
+            vec![
+                (Clause::from_cnf(vec![1, 2]), 10u64),
+                (Clause::from_cnf(vec![-1]), 3),
+                (Clause::from_cnf(vec![-2]), 12),
+                (Clause::from_cnf(vec![1]), 9),
+            ],
+        );
+
+        let (hard, soft) = instance.split_hard_soft(10);
+        assert_eq!(hard.num_vars(), 2);
+        assert_eq!(
+            hard.get_clauses(),
+            &vec![Clause::from_cnf(vec![1, 2]), Clause::from_cnf(vec![-2])]
+        );
+        assert_eq!(
+            soft,
+            vec![
+                (Clause::from_cnf(vec![-1]), 3),
+                (Clause::from_cnf(vec![1]), 9)
+            ]
+        );
+    }
+
+    #[test]
+    fn float_weights() {
+        let instance = WeightedInstance::new(
+            Instance::with_clauses(
+                1,
+                vec![Clause::from_cnf(vec![1]), Clause::from_cnf(vec![-1])],
+            ),
+            vec![0.25, 0.5],
+        );
+
+        assert_eq!(instance.weighted_sat_sum(&BoolVec::from([false])), 0.5);
+    }
+
+    #[test]
+    fn wcnf_reader() {
+        let wcnf = "c comment\np wcnf 2 3 10\n10 1 2 0\n3 -1 0\n7 -2 0\n";
+        let instance = Instance::from_wcnf_reader::<u64, _>(wcnf.as_bytes()).unwrap();
+        assert_eq!(instance.instance().num_vars(), 2);
+        assert_eq!(instance.weights(), &[10, 3, 7]);
+        assert_eq!(
+            instance.instance().get_clauses(),
+            &vec![
+                Clause::from_cnf(vec![1, 2]),
+                Clause::from_cnf(vec![-1]),
+                Clause::from_cnf(vec![-2])
+            ]
+        );
+
+        let wcnf = "p wcnf 1 2\n-0.5 1 0\n-1.25 -1 0\n";
+        let instance = Instance::from_wcnf_reader::<f64, _>(wcnf.as_bytes()).unwrap();
+        assert_eq!(instance.weighted_sat_sum(&BoolVec::from([true])), -0.5);
+
+        assert!(Instance::from_wcnf_reader::<u64, _>(wcnf.as_bytes()).is_err());
+        assert!(Instance::from_wcnf_reader::<u64, _>("p wcnf 1 1\n2 0\n".as_bytes()).is_ok());
+        assert!(Instance::from_wcnf_reader::<u64, _>("p wcnf 1 1\n2\n".as_bytes()).is_err());
+        assert!(Instance::from_wcnf_reader::<u64, _>("p wcnf 1 1\n2 3 0\n".as_bytes()).is_err());
+        assert!(Instance::from_wcnf_reader::<u64, _>("p cnf 1 1\n2 1 0\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_panic() {
+        WeightedInstance::new(Instance::with_clauses(1, vec![]), vec![1]);
+    }
+}
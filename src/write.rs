@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+
+use crate::clause::Clause;
+
+/// Options of the CNF writer, see `Instance::to_writer_with`.
+/// The default options write each clause on a single line.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WriteOptions {
+    /// The maximal width of the clause lines, for tools that cannot read long lines.
+    /// Longer clauses are continued on the next lines, which DIMACS allows as only `0` terminates a clause.
+    /// A literal wider than the limit is written alone on its line.
+    /// The header and quantifier lines are never wrapped.
+    pub max_line_width: Option<usize>,
+}
+
+/// Writes the clause in CNF, terminated by 0, wrapping it at `options.max_line_width`.
+pub(crate) fn write_clause<W: Write>(
+    w: &mut W,
+    clause: &Clause,
+    options: &WriteOptions,
+) -> io::Result<()> {
+    let Some(max_width) = options.max_line_width else {
+        return writeln!(w, "{clause:#}");
+    };
+
+    let mut width = 0;
+    let tokens = clause
+        .get_literals()
+        .iter()
+        .map(|lit| lit.as_cnf().to_string())
+        .chain(["0".to_string()]);
+    for token in tokens {
+        if width != 0 && width + 1 + token.len() > max_width {
+            writeln!(w)?;
+            width = 0;
+        }
+        if width != 0 {
+            write!(w, " ")?;
+            width += 1;
+        }
+        write!(w, "{token}")?;
+        width += token.len();
+    }
+
+    writeln!(w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped() {
+        let clause = Clause::from_cnf(vec![1, -22, 333, -4444, 5]);
+        let write = |max_line_width| {
+            let mut out = vec![];
+            write_clause(&mut out, &clause, &WriteOptions { max_line_width }).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(write(None), "1 -22 333 -4444 5 0\n");
+        assert_eq!(write(Some(10)), "1 -22 333\n-4444 5 0\n");
+        assert_eq!(write(Some(3)), "1\n-22\n333\n-4444\n5 0\n");
+        assert_eq!(write(Some(100)), "1 -22 333 -4444 5 0\n");
+    }
+}